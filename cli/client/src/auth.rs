@@ -0,0 +1,40 @@
+use reqwest::header::{HeaderName, HeaderValue, AUTHORIZATION};
+use std::fmt;
+
+use crate::error::{Result, VpsError};
+
+/// Credentials sent with every request.
+#[derive(Clone)]
+pub enum Auth {
+    /// Sent as `Authorization: Bearer <token>`.
+    Bearer(String),
+    /// Sent as `X-API-Key: <key>`.
+    ApiKey(String),
+}
+
+impl Auth {
+    /// The header carrying these credentials, marked sensitive so it is
+    /// never logged.
+    pub(crate) fn header(&self) -> Result<(HeaderName, HeaderValue)> {
+        let (name, value) = match self {
+            Auth::Bearer(token) => (AUTHORIZATION, format!("Bearer {}", token)),
+            Auth::ApiKey(key) => (HeaderName::from_static("x-api-key"), key.clone()),
+        };
+
+        let mut value = HeaderValue::from_str(&value).map_err(|_| {
+            VpsError::Config("API token contains characters not allowed in a header".to_string())
+        })?;
+        value.set_sensitive(true);
+
+        Ok((name, value))
+    }
+}
+
+impl fmt::Debug for Auth {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Auth::Bearer(_) => write!(f, "Bearer(<redacted>)"),
+            Auth::ApiKey(_) => write!(f, "ApiKey(<redacted>)"),
+        }
+    }
+}
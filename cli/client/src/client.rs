@@ -0,0 +1,699 @@
+use reqwest::header::HeaderMap;
+use reqwest::{Client, ClientBuilder, Method, RequestBuilder, Response, StatusCode};
+use serde::de::DeserializeOwned;
+use std::time::Duration;
+
+use crate::auth::Auth;
+use crate::error::{self, Result, TimeoutPhase, VpsError};
+use crate::lookup;
+use crate::models::{
+    ApiResponse, CloneRequest, ResizeRequest, Snapshot, SnapshotRequest, SnapshotType,
+    UpdateRequest, VMRequest, VM,
+};
+use crate::retry::{self, RetryPolicy};
+use crate::tls::TlsOptions;
+
+/// Header carrying the key that lets the server deduplicate retried creates.
+const IDEMPOTENCY_KEY_HEADER: &str = "Idempotency-Key";
+
+/// URL prefix selecting the Unix domain socket transport.
+const UNIX_SCHEME: &str = "unix://";
+
+const DEFAULT_TIMEOUT: Duration = Duration::from_secs(30);
+const DEFAULT_CONNECT_TIMEOUT: Duration = Duration::from_secs(10);
+const DEFAULT_LONG_OPERATION_TIMEOUT: Duration = Duration::from_secs(600);
+
+/// Client for the Firecracker VPS API.
+pub struct VPSClient {
+    client: Client,
+    /// The server URL as configured, e.g. `unix:///run/fc-vps.sock`.
+    base_url: String,
+    /// Prefix for request URLs; differs from `base_url` for Unix sockets.
+    api_url: String,
+    verbose: bool,
+    retry_policy: RetryPolicy,
+    timeout: Duration,
+    connect_timeout: Duration,
+    long_operation_timeout: Duration,
+}
+
+/// Configures and builds a [`VPSClient`].
+pub struct VPSClientBuilder {
+    base_url: String,
+    verbose: bool,
+    retry_policy: RetryPolicy,
+    timeout: Duration,
+    connect_timeout: Duration,
+    long_operation_timeout: Duration,
+    auth: Option<Auth>,
+    tls: TlsOptions,
+}
+
+impl VPSClientBuilder {
+    /// Log every call to stdout.
+    pub fn verbose(mut self, verbose: bool) -> Self {
+        self.verbose = verbose;
+        self
+    }
+
+    /// Retry policy for failed requests. Defaults to [`RetryPolicy::default`].
+    pub fn retry_policy(mut self, retry_policy: RetryPolicy) -> Self {
+        self.retry_policy = retry_policy;
+        self
+    }
+
+    /// Limit on a whole request, from connecting to reading the response
+    /// body. Defaults to 30 seconds.
+    pub fn timeout(mut self, timeout: Duration) -> Self {
+        self.timeout = timeout;
+        self
+    }
+
+    /// Limit on establishing the connection. Defaults to 10 seconds.
+    pub fn connect_timeout(mut self, connect_timeout: Duration) -> Self {
+        self.connect_timeout = connect_timeout;
+        self
+    }
+
+    /// Limit used instead of [`timeout`](Self::timeout) for calls that make
+    /// the server do slow work, such as creating a VM (which copies the base
+    /// image) or deleting one. Defaults to 10 minutes.
+    pub fn long_operation_timeout(mut self, long_operation_timeout: Duration) -> Self {
+        self.long_operation_timeout = long_operation_timeout;
+        self
+    }
+
+    /// Credentials to send with every request.
+    pub fn auth(mut self, auth: Auth) -> Self {
+        self.auth = Some(auth);
+        self
+    }
+
+    /// CA bundle, client certificate and verification settings for HTTPS.
+    pub fn tls(mut self, tls: TlsOptions) -> Self {
+        self.tls = tls;
+        self
+    }
+
+    pub fn build(self) -> Result<VPSClient> {
+        let mut headers = HeaderMap::new();
+        if let Some(auth) = &self.auth {
+            let (name, value) = auth.header()?;
+            headers.insert(name, value);
+        }
+
+        let mut builder = Client::builder()
+            .default_headers(headers)
+            .timeout(self.timeout)
+            .connect_timeout(self.connect_timeout);
+
+        let api_url = match self.base_url.strip_prefix(UNIX_SCHEME) {
+            Some(socket_path) => {
+                builder = unix_socket(builder, socket_path)?;
+                // Host and port are ignored; every connection goes to the socket.
+                "http://localhost".to_string()
+            }
+            None => self.base_url.trim_end_matches('/').to_string(),
+        };
+
+        let client = self
+            .tls
+            .apply(builder)?
+            .build()
+            .map_err(|e| VpsError::Config(format!("Failed to build HTTP client: {}", e)))?;
+
+        Ok(VPSClient {
+            client,
+            base_url: self.base_url,
+            api_url,
+            verbose: self.verbose,
+            retry_policy: self.retry_policy,
+            timeout: self.timeout,
+            connect_timeout: self.connect_timeout,
+            long_operation_timeout: self.long_operation_timeout,
+        })
+    }
+}
+
+impl VPSClient {
+    /// Creates a client for the server at `base_url`, e.g.
+    /// `http://localhost:8080`, with default settings. With `verbose` set,
+    /// every call logs what it is doing to stdout.
+    ///
+    /// Fails with [`VpsError::Config`] if the client can't be built, e.g.
+    /// when the TLS backend can't be initialized or for a `unix://` URL on a
+    /// platform without Unix sockets.
+    pub fn try_new(base_url: impl Into<String>, verbose: bool) -> Result<Self> {
+        Self::builder(base_url).verbose(verbose).build()
+    }
+
+    /// Like [`try_new`](Self::try_new), but panics if the client can't be
+    /// built.
+    pub fn new(base_url: String, verbose: bool) -> Self {
+        Self::try_new(base_url, verbose).expect("failed to build the HTTP client")
+    }
+
+    /// Starts configuring a client for the server at `base_url`: either an
+    /// `http://` or `https://` URL, or `unix:///path/to/socket` to speak HTTP
+    /// over a Unix domain socket.
+    pub fn builder(base_url: impl Into<String>) -> VPSClientBuilder {
+        VPSClientBuilder {
+            base_url: base_url.into(),
+            verbose: false,
+            retry_policy: RetryPolicy::default(),
+            timeout: DEFAULT_TIMEOUT,
+            connect_timeout: DEFAULT_CONNECT_TIMEOUT,
+            long_operation_timeout: DEFAULT_LONG_OPERATION_TIMEOUT,
+            auth: None,
+            tls: TlsOptions::default(),
+        }
+    }
+
+    /// Base URL of the API server this client talks to.
+    pub fn base_url(&self) -> &str {
+        &self.base_url
+    }
+
+    fn url(&self, path: &str) -> String {
+        format!("{}{}", self.api_url, path)
+    }
+
+    /// Sends `request` and runs the response through [`handle_response`],
+    /// retrying according to the client's [`RetryPolicy`]. Requests that
+    /// aren't `idempotent` are only retried if they never reached the server.
+    /// A retried DELETE that finds nothing succeeds, since an earlier attempt
+    /// may have deleted the resource before its response was lost. `phase`
+    /// picks the timeout the request runs under.
+    async fn execute<T: DeserializeOwned>(
+        &self,
+        request: RequestBuilder,
+        idempotent: bool,
+        phase: TimeoutPhase,
+    ) -> Result<ApiResponse<T>> {
+        let request = match phase {
+            TimeoutPhase::LongOperation => request.timeout(self.long_operation_timeout),
+            _ => request,
+        };
+        let is_delete = request
+            .try_clone()
+            .and_then(|r| r.build().ok())
+            .is_some_and(|r| r.method() == Method::DELETE);
+        let mut attempt = 1;
+
+        loop {
+            // Bodies are always buffered JSON, so cloning can't fail.
+            let result = match request
+                .try_clone()
+                .expect("request body should be cloneable")
+                .send()
+                .await
+            {
+                Ok(response) => handle_response(response).await,
+                Err(e) => Err(VpsError::Transport(e)),
+            }
+            .map_err(|err| self.classify_timeout(err, phase));
+
+            match result {
+                Err(err)
+                    if attempt < self.retry_policy.max_attempts
+                        && retry::is_retryable(&err, idempotent) =>
+                {
+                    let delay = self.retry_policy.backoff(attempt);
+                    if self.verbose {
+                        println!(
+                            "Request failed ({}), retrying in {}ms (attempt {}/{})",
+                            err,
+                            delay.as_millis(),
+                            attempt + 1,
+                            self.retry_policy.max_attempts
+                        );
+                    }
+                    tokio::time::sleep(delay).await;
+                    attempt += 1;
+                }
+                Err(VpsError::NotFound(_)) if is_delete && attempt > 1 => {
+                    return Ok(ApiResponse {
+                        success: true,
+                        message: "Already deleted".to_string(),
+                        data: None,
+                    });
+                }
+                result => return result,
+            }
+        }
+    }
+
+    /// Turns a timed-out transport error into [`VpsError::Timeout`], naming
+    /// the phase and the limit that was hit.
+    fn classify_timeout(&self, err: VpsError, phase: TimeoutPhase) -> VpsError {
+        match err {
+            VpsError::Transport(e) if e.is_timeout() => {
+                let (phase, limit) = if e.is_connect() {
+                    (TimeoutPhase::Connect, self.connect_timeout)
+                } else if phase == TimeoutPhase::LongOperation {
+                    (phase, self.long_operation_timeout)
+                } else {
+                    (TimeoutPhase::Request, self.timeout)
+                };
+                VpsError::Timeout { phase, limit }
+            }
+            err => err,
+        }
+    }
+
+    /// Creates a new VPS. The returned VM is in the `created` state.
+    ///
+    /// Every attempt carries the same `Idempotency-Key`, so a retry after a
+    /// dropped response doesn't create a second VM.
+    pub async fn create_vm(&self, request: VMRequest) -> Result<VM> {
+        if self.verbose {
+            println!(
+                "Creating VPS with request: {}",
+                serde_json::to_string_pretty(&request).unwrap_or_default()
+            );
+        }
+
+        let idempotency_key = uuid::Uuid::new_v4().to_string();
+        let api_response: ApiResponse<VM> = self
+            .execute(
+                self.client
+                    .post(self.url("/api/v1/vms"))
+                    .header(IDEMPOTENCY_KEY_HEADER, idempotency_key)
+                    .json(&request),
+                true,
+                TimeoutPhase::LongOperation,
+            )
+            .await?;
+
+        api_response.data.ok_or(VpsError::MissingData)
+    }
+
+    /// Creates a new VPS from a copy of another one's disk, with its own IP
+    /// address and TAP device. A running source is paused while its disk is
+    /// copied. Like [`create_vm`](Self::create_vm), retries are
+    /// deduplicated with an `Idempotency-Key`.
+    pub async fn clone_vm(&self, source_id: &str, request: CloneRequest) -> Result<VM> {
+        if self.verbose {
+            println!(
+                "Cloning VPS {} with request: {}",
+                source_id,
+                serde_json::to_string_pretty(&request).unwrap_or_default()
+            );
+        }
+
+        let idempotency_key = uuid::Uuid::new_v4().to_string();
+        let api_response: ApiResponse<VM> = self
+            .execute(
+                self.client
+                    .post(self.url(&format!("/api/v1/vms/{}/clone", source_id)))
+                    .header(IDEMPOTENCY_KEY_HEADER, idempotency_key)
+                    .json(&request),
+                true,
+                TimeoutPhase::LongOperation,
+            )
+            .await?;
+
+        api_response.data.ok_or(VpsError::MissingData)
+    }
+
+    /// Changes the name, description or labels of a VPS and returns it as
+    /// updated. A name another VPS already has is a [`VpsError::Conflict`].
+    pub async fn update_vm(&self, id: &str, request: UpdateRequest) -> Result<VM> {
+        if self.verbose {
+            println!(
+                "Updating VPS {} with request: {}",
+                id,
+                serde_json::to_string_pretty(&request).unwrap_or_default()
+            );
+        }
+
+        let api_response: ApiResponse<VM> = self
+            .execute(
+                self.client
+                    .patch(self.url(&format!("/api/v1/vms/{}", id)))
+                    .json(&request),
+                true,
+                TimeoutPhase::Request,
+            )
+            .await?;
+
+        api_response.data.ok_or(VpsError::MissingData)
+    }
+
+    /// Changes the CPU count, memory or disk size of a stopped VPS and
+    /// returns it as updated. Growing the disk resizes its filesystem.
+    pub async fn resize_vm(&self, id: &str, request: ResizeRequest) -> Result<VM> {
+        if self.verbose {
+            println!(
+                "Resizing VPS {} with request: {}",
+                id,
+                serde_json::to_string_pretty(&request).unwrap_or_default()
+            );
+        }
+
+        let api_response: ApiResponse<VM> = self
+            .execute(
+                self.client
+                    .post(self.url(&format!("/api/v1/vms/{}/resize", id)))
+                    .json(&request),
+                // Sizes are absolute, so a repeat is harmless
+                true,
+                TimeoutPhase::LongOperation,
+            )
+            .await?;
+
+        api_response.data.ok_or(VpsError::MissingData)
+    }
+
+    /// Lists every VPS known to the server.
+    pub async fn list_vms(&self) -> Result<Vec<VM>> {
+        if self.verbose {
+            println!("Fetching VPS list...");
+        }
+
+        let api_response: ApiResponse<Vec<VM>> = self
+            .execute(
+                self.client.get(self.url("/api/v1/vms")),
+                true,
+                TimeoutPhase::Request,
+            )
+            .await?;
+
+        Ok(api_response.data.unwrap_or_default())
+    }
+
+    /// Fetches a single VPS by its full ID.
+    pub async fn get_vm(&self, id: &str) -> Result<VM> {
+        if self.verbose {
+            println!("Fetching VPS details for: {}", id);
+        }
+
+        let api_response: ApiResponse<VM> = self
+            .execute(
+                self.client.get(self.url(&format!("/api/v1/vms/{}", id))),
+                true,
+                TimeoutPhase::Request,
+            )
+            .await?;
+
+        api_response.data.ok_or(VpsError::MissingData)
+    }
+
+    /// Boots the VPS with the given ID.
+    pub async fn start_vm(&self, id: &str) -> Result<()> {
+        if self.verbose {
+            println!("Starting VPS: {}", id);
+        }
+
+        self.execute::<()>(
+            self.client
+                .post(self.url(&format!("/api/v1/vms/{}/start", id))),
+            false,
+            TimeoutPhase::LongOperation,
+        )
+        .await?;
+
+        Ok(())
+    }
+
+    /// Shuts down the VPS with the given ID. Returns once its Firecracker
+    /// process has exited, which can take as long as the guest needs to
+    /// shut down.
+    pub async fn stop_vm(&self, id: &str) -> Result<()> {
+        if self.verbose {
+            println!("Stopping VPS: {}", id);
+        }
+
+        self.execute::<()>(
+            self.client
+                .post(self.url(&format!("/api/v1/vms/{}/stop", id))),
+            false,
+            TimeoutPhase::LongOperation,
+        )
+        .await?;
+
+        Ok(())
+    }
+
+    /// Freezes a running VPS in place; its memory is kept until it is
+    /// resumed.
+    pub async fn pause_vm(&self, id: &str) -> Result<()> {
+        if self.verbose {
+            println!("Pausing VPS: {}", id);
+        }
+
+        self.execute::<()>(
+            self.client
+                .post(self.url(&format!("/api/v1/vms/{}/pause", id))),
+            false,
+            TimeoutPhase::Request,
+        )
+        .await?;
+
+        Ok(())
+    }
+
+    /// Lets a paused VPS run again.
+    pub async fn resume_vm(&self, id: &str) -> Result<()> {
+        if self.verbose {
+            println!("Resuming VPS: {}", id);
+        }
+
+        self.execute::<()>(
+            self.client
+                .post(self.url(&format!("/api/v1/vms/{}/resume", id))),
+            false,
+            TimeoutPhase::Request,
+        )
+        .await?;
+
+        Ok(())
+    }
+
+    /// Reboots the guest of a running VPS by sending it Ctrl+Alt+Del. The
+    /// server boots the VPS again once the guest has shut down.
+    pub async fn reboot_vm(&self, id: &str) -> Result<()> {
+        if self.verbose {
+            println!("Rebooting VPS: {}", id);
+        }
+
+        self.execute::<()>(
+            self.client
+                .post(self.url(&format!("/api/v1/vms/{}/reboot", id))),
+            false,
+            TimeoutPhase::LongOperation,
+        )
+        .await?;
+
+        Ok(())
+    }
+
+    /// Deletes the VPS with the given ID along with its disk and network
+    /// resources. A retry that finds the VPS already gone counts as success.
+    pub async fn delete_vm(&self, id: &str) -> Result<()> {
+        if self.verbose {
+            println!("Deleting VPS: {}", id);
+        }
+
+        self.execute::<()>(
+            self.client.delete(self.url(&format!("/api/v1/vms/{}", id))),
+            true,
+            TimeoutPhase::LongOperation,
+        )
+        .await?;
+
+        Ok(())
+    }
+
+    /// Snapshots the memory, VM state and disk of a running or paused VPS.
+    /// The VPS is paused while the snapshot is taken.
+    pub async fn create_snapshot(
+        &self,
+        vm_id: &str,
+        snapshot_type: SnapshotType,
+    ) -> Result<Snapshot> {
+        if self.verbose {
+            println!("Creating {} snapshot of VPS: {}", snapshot_type, vm_id);
+        }
+
+        let api_response: ApiResponse<Snapshot> = self
+            .execute(
+                self.client
+                    .post(self.url(&format!("/api/v1/vms/{}/snapshots", vm_id)))
+                    .json(&SnapshotRequest { snapshot_type }),
+                false,
+                TimeoutPhase::LongOperation,
+            )
+            .await?;
+
+        api_response.data.ok_or(VpsError::MissingData)
+    }
+
+    /// Lists the snapshots of a VPS, oldest first.
+    pub async fn list_snapshots(&self, vm_id: &str) -> Result<Vec<Snapshot>> {
+        if self.verbose {
+            println!("Fetching snapshots of VPS: {}", vm_id);
+        }
+
+        let api_response: ApiResponse<Vec<Snapshot>> = self
+            .execute(
+                self.client
+                    .get(self.url(&format!("/api/v1/vms/{}/snapshots", vm_id))),
+                true,
+                TimeoutPhase::Request,
+            )
+            .await?;
+
+        Ok(api_response.data.unwrap_or_default())
+    }
+
+    /// Rolls a stopped VPS back to a snapshot and boots it from there.
+    pub async fn restore_snapshot(&self, vm_id: &str, snapshot_id: &str) -> Result<()> {
+        if self.verbose {
+            println!("Restoring snapshot {} of VPS: {}", snapshot_id, vm_id);
+        }
+
+        self.execute::<()>(
+            self.client.post(self.url(&format!(
+                "/api/v1/vms/{}/snapshots/{}/restore",
+                vm_id, snapshot_id
+            ))),
+            false,
+            TimeoutPhase::LongOperation,
+        )
+        .await?;
+
+        Ok(())
+    }
+
+    /// Deletes a snapshot and its files. Like [`delete_vm`](Self::delete_vm),
+    /// a retry that finds it already gone counts as success.
+    pub async fn delete_snapshot(&self, vm_id: &str, snapshot_id: &str) -> Result<()> {
+        if self.verbose {
+            println!("Deleting snapshot {} of VPS: {}", snapshot_id, vm_id);
+        }
+
+        self.execute::<()>(
+            self.client
+                .delete(self.url(&format!("/api/v1/vms/{}/snapshots/{}", vm_id, snapshot_id))),
+            true,
+            TimeoutPhase::Request,
+        )
+        .await?;
+
+        Ok(())
+    }
+
+    /// Returns `true` if the server's `/health` endpoint answers with a
+    /// success status. Rejected credentials are reported as errors rather
+    /// than as an unhealthy server.
+    pub async fn health_check(&self) -> Result<bool> {
+        if self.verbose {
+            println!("Checking service health...");
+        }
+
+        let response = self
+            .client
+            .get(self.url("/health"))
+            .send()
+            .await
+            .map_err(|e| self.classify_timeout(VpsError::Transport(e), TimeoutPhase::Request))?;
+
+        let status = response.status();
+        if status == StatusCode::UNAUTHORIZED || status == StatusCode::FORBIDDEN {
+            let body = response.text().await.unwrap_or_default();
+            return Err(VpsError::from_status(status, error::snippet(&body)));
+        }
+
+        Ok(status.is_success())
+    }
+
+    /// Looks up exactly one VPS by full ID, name, unique ID prefix or a
+    /// name pattern matching a single VPS. Several matches are a
+    /// [`VpsError::Ambiguous`] listing the candidates.
+    pub async fn find_vm_by_name_or_id(&self, name_or_id: &str) -> Result<VM> {
+        let mut vms = self.find_vms_by_name_or_id(name_or_id).await?;
+        if vms.len() > 1 {
+            return Err(lookup::ambiguous(name_or_id, &vms));
+        }
+        Ok(vms.remove(0))
+    }
+
+    /// Looks up every VPS `query` refers to: a full ID, an exact name, a
+    /// glob pattern on names (`web-*`) or an ID prefix of at least four
+    /// characters. Only a pattern may match several VPS instances. Never
+    /// returns an empty list; no match is a [`VpsError::NotFound`]
+    /// suggesting similar names.
+    pub async fn find_vms_by_name_or_id(&self, query: &str) -> Result<Vec<VM>> {
+        // A full ID needs only one request
+        if !lookup::is_pattern(query) {
+            match self.get_vm(query).await {
+                Ok(vm) => return Ok(vec![vm]),
+                Err(VpsError::NotFound(_)) => {}
+                Err(VpsError::Http { status, .. }) if status == StatusCode::NOT_FOUND => {}
+                Err(err) => return Err(err),
+            }
+        }
+
+        lookup::matching(self.list_vms().await?, query)
+    }
+}
+
+#[cfg(unix)]
+fn unix_socket(builder: ClientBuilder, socket_path: &str) -> Result<ClientBuilder> {
+    if socket_path.is_empty() {
+        return Err(VpsError::Config(
+            "Unix socket URL has no path, expected unix:///path/to/socket".to_string(),
+        ));
+    }
+    Ok(builder.unix_socket(socket_path))
+}
+
+#[cfg(not(unix))]
+fn unix_socket(_builder: ClientBuilder, _socket_path: &str) -> Result<ClientBuilder> {
+    Err(VpsError::Config(
+        "Unix socket URLs are not supported on this platform".to_string(),
+    ))
+}
+
+/// Shared response handling for every API call.
+///
+/// Error statuses are reported with the server's `message` when the body is
+/// the usual `{success, message, data}` envelope, and with the raw body
+/// otherwise (gin's plain-text 404, a proxy's HTML 502 page, ...). Success
+/// statuses must carry an envelope with `success: true`.
+async fn handle_response<T: DeserializeOwned>(response: Response) -> Result<ApiResponse<T>> {
+    let status = response.status();
+    let body = response.text().await.map_err(VpsError::Transport)?;
+
+    if !status.is_success() {
+        // Decode loosely so an unexpected `data` shape doesn't hide the message.
+        return Err(
+            match serde_json::from_str::<ApiResponse<serde_json::Value>>(&body) {
+                Ok(envelope) => VpsError::from_status(status, envelope.message),
+                // Auth proxies answer in plain text; still report these as auth failures.
+                Err(_) if status == StatusCode::UNAUTHORIZED || status == StatusCode::FORBIDDEN => {
+                    VpsError::from_status(status, error::snippet(&body))
+                }
+                Err(_) => VpsError::Http { status, body },
+            },
+        );
+    }
+
+    let api_response: ApiResponse<T> = match serde_json::from_str(&body) {
+        Ok(api_response) => api_response,
+        Err(error) => {
+            return Err(VpsError::Decode {
+                status,
+                error,
+                body,
+            })
+        }
+    };
+
+    if !api_response.success {
+        return Err(VpsError::from_status(status, api_response.message));
+    }
+
+    Ok(api_response)
+}
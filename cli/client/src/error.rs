@@ -0,0 +1,137 @@
+use reqwest::StatusCode;
+use std::fmt;
+use std::time::Duration;
+use thiserror::Error;
+
+/// Errors returned by [`VPSClient`](crate::VPSClient).
+///
+/// Transport, HTTP and decode failures describe problems talking to the
+/// server. The remaining variants carry the `message` the server reported
+/// when it answered with `success: false`.
+#[derive(Debug, Error)]
+pub enum VpsError {
+    /// The client itself could not be set up (bad URL, unreadable
+    /// certificate, ...).
+    #[error("Invalid client configuration: {0}")]
+    Config(String),
+
+    /// The request never got a response (DNS, connect, reset, ...).
+    #[error("Transport error")]
+    Transport(#[source] reqwest::Error),
+
+    /// A configured time limit was hit.
+    #[error("Timed out {phase} after {limit:?}")]
+    Timeout {
+        phase: TimeoutPhase,
+        limit: Duration,
+    },
+
+    /// The server answered with a non-success HTTP status and a body that
+    /// is not an API envelope, e.g. a proxy error page.
+    #[error("HTTP {status}: {}", snippet(body))]
+    Http { status: StatusCode, body: String },
+
+    /// A success response whose body was not the expected JSON.
+    #[error("Failed to decode response (HTTP {status}): {error}")]
+    Decode {
+        status: StatusCode,
+        error: serde_json::Error,
+        body: String,
+    },
+
+    /// The response envelope reported success but carried no data.
+    #[error("No data in API response")]
+    MissingData,
+
+    /// The server (or a proxy in front of it) rejected the credentials.
+    #[error("Authentication failed: {0}")]
+    Unauthorized(String),
+
+    /// The credentials are valid but not allowed to do this.
+    #[error("Permission denied: {0}")]
+    Forbidden(String),
+
+    /// The requested VPS does not exist.
+    #[error("Not found: {0}")]
+    NotFound(String),
+
+    /// A name pattern or ID prefix matched several VPS instances where one
+    /// was needed.
+    #[error("Ambiguous: {0}")]
+    Ambiguous(String),
+
+    /// The VPS is in the wrong state for the operation, e.g. already running.
+    #[error("Conflict: {0}")]
+    Conflict(String),
+
+    /// The server rejected the request parameters.
+    #[error("Validation failed: {0}")]
+    Validation(String),
+
+    /// The host is out of resources (IP addresses, disk space, VM slots).
+    #[error("Insufficient capacity: {0}")]
+    Capacity(String),
+
+    /// Any other server-reported failure.
+    #[error("API Error: {0}")]
+    Api(String),
+}
+
+impl VpsError {
+    /// Classifies a `success: false` envelope by HTTP status; anything
+    /// without a more specific status is a [`VpsError::Api`]. The status
+    /// line is appended to the message.
+    pub(crate) fn from_status(status: StatusCode, message: String) -> Self {
+        let variant = match status {
+            StatusCode::UNAUTHORIZED => VpsError::Unauthorized,
+            StatusCode::FORBIDDEN => VpsError::Forbidden,
+            StatusCode::NOT_FOUND => VpsError::NotFound,
+            StatusCode::CONFLICT => VpsError::Conflict,
+            StatusCode::BAD_REQUEST | StatusCode::UNPROCESSABLE_ENTITY => VpsError::Validation,
+            StatusCode::INSUFFICIENT_STORAGE => VpsError::Capacity,
+            _ => VpsError::Api,
+        };
+
+        variant(format!("{} (HTTP {})", message, status))
+    }
+}
+
+/// The stage of a request during which a [`VpsError::Timeout`] fired.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TimeoutPhase {
+    /// Establishing the connection to the server.
+    Connect,
+    /// Sending a regular request and reading its response.
+    Request,
+    /// Waiting on a slow server-side operation such as creating a VM.
+    LongOperation,
+}
+
+impl fmt::Display for TimeoutPhase {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            TimeoutPhase::Connect => write!(f, "connecting to the server"),
+            TimeoutPhase::Request => write!(f, "waiting for the response"),
+            TimeoutPhase::LongOperation => write!(f, "waiting for the long-running operation"),
+        }
+    }
+}
+
+/// Condenses a raw response body for display: whitespace is collapsed and
+/// long bodies (HTML error pages) are cut short.
+pub(crate) fn snippet(body: &str) -> String {
+    const MAX_CHARS: usize = 200;
+
+    let collapsed = body.split_whitespace().collect::<Vec<_>>().join(" ");
+    if collapsed.is_empty() {
+        return "<empty body>".to_string();
+    }
+    if collapsed.chars().count() > MAX_CHARS {
+        let truncated: String = collapsed.chars().take(MAX_CHARS).collect();
+        return format!("{}...", truncated);
+    }
+    collapsed
+}
+
+/// Result alias used throughout the client.
+pub type Result<T> = std::result::Result<T, VpsError>;
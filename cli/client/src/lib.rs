@@ -0,0 +1,44 @@
+//! Async client for the Firecracker VPS API.
+//!
+//! This is the same client the `fc-vps` CLI uses. It wraps the REST API
+//! served under `/api/v1` and decodes its `{success, message, data}`
+//! envelope into typed models.
+//!
+//! ```no_run
+//! use fc_vps_client::{VMRequest, VPSClient};
+//!
+//! # async fn run() -> fc_vps_client::Result<()> {
+//! let client = VPSClient::try_new("http://localhost:8080", false)?;
+//!
+//! let vm = client
+//!     .create_vm(VMRequest {
+//!         name: "web-01".to_string(),
+//!         cpu: 2,
+//!         memory: 1024,
+//!         disk_size: 20,
+//!         image: "ubuntu-24.04".to_string(),
+//!         labels: [("team".to_string(), "payments".to_string())].into(),
+//!     })
+//!     .await?;
+//! client.start_vm(&vm.id).await?;
+//! # Ok(())
+//! # }
+//! ```
+
+mod auth;
+mod client;
+mod error;
+mod lookup;
+mod models;
+mod retry;
+mod tls;
+
+pub use auth::Auth;
+pub use client::{VPSClient, VPSClientBuilder};
+pub use error::{Result, TimeoutPhase, VpsError};
+pub use models::{
+    ApiResponse, CloneRequest, ResizeRequest, Snapshot, SnapshotRequest, SnapshotType,
+    UpdateRequest, VMRequest, VM,
+};
+pub use retry::RetryPolicy;
+pub use tls::TlsOptions;
@@ -0,0 +1,207 @@
+use crate::error::{Result, VpsError};
+use crate::models::VM;
+use glob::Pattern;
+
+/// Shortest ID prefix matched, so that short hex-looking names (`db`,
+/// `cafe`) are not taken for IDs.
+const MIN_PREFIX_LEN: usize = 4;
+
+/// How similar a name must be to be offered as a suggestion (Jaro-Winkler).
+const SUGGESTION_THRESHOLD: f64 = 0.8;
+
+const MAX_SUGGESTIONS: usize = 3;
+
+/// Whether `query` is a glob pattern rather than a name or ID.
+pub(crate) fn is_pattern(query: &str) -> bool {
+    query.contains(['*', '?', '['])
+}
+
+/// Picks the VPS instances `query` refers to, trying in order:
+///
+/// 1. an exact name or full ID,
+/// 2. a glob pattern on names (`web-*`),
+/// 3. an ID prefix of at least [`MIN_PREFIX_LEN`] characters, such as the
+///    short ID shown by `fc-vps list`.
+///
+/// Only a pattern may match several VPS instances; a name or ID prefix
+/// matching more than one is [`ambiguous`]. Nothing matching is a
+/// [`VpsError::NotFound`] that suggests similar names.
+pub(crate) fn matching(vms: Vec<VM>, query: &str) -> Result<Vec<VM>> {
+    let exact: Vec<&VM> = vms
+        .iter()
+        .filter(|vm| vm.name == query || vm.id == query)
+        .collect();
+
+    let found: Vec<&VM> = if !exact.is_empty() {
+        exact
+    } else if is_pattern(query) {
+        let pattern = Pattern::new(query).map_err(|e| {
+            VpsError::Validation(format!("Invalid name pattern '{}': {}", query, e))
+        })?;
+        vms.iter().filter(|vm| pattern.matches(&vm.name)).collect()
+    } else if query.len() >= MIN_PREFIX_LEN {
+        let prefix = query.to_ascii_lowercase();
+        vms.iter().filter(|vm| vm.id.starts_with(&prefix)).collect()
+    } else {
+        Vec::new()
+    };
+
+    let found: Vec<VM> = found.into_iter().cloned().collect();
+    match found.len() {
+        0 => Err(not_found(&vms, query)),
+        1 => Ok(found),
+        _ if is_pattern(query) => Ok(found),
+        _ => Err(ambiguous(query, &found)),
+    }
+}
+
+/// The error for more than one match where exactly one VPS is needed.
+pub(crate) fn ambiguous(query: &str, vms: &[VM]) -> VpsError {
+    let candidates: Vec<String> = vms
+        .iter()
+        .map(|vm| format!("{} ({})", vm.name, vm.id.get(..8).unwrap_or(&vm.id)))
+        .collect();
+
+    VpsError::Ambiguous(format!(
+        "'{}' matches {} VPS instances, candidates: {}",
+        query,
+        vms.len(),
+        candidates.join(", ")
+    ))
+}
+
+fn not_found(vms: &[VM], query: &str) -> VpsError {
+    let mut scored: Vec<(f64, &str)> = vms
+        .iter()
+        .map(|vm| (strsim::jaro_winkler(query, &vm.name), vm.name.as_str()))
+        .filter(|(score, _)| *score >= SUGGESTION_THRESHOLD)
+        .collect();
+    scored.sort_by(|a, b| b.0.total_cmp(&a.0));
+
+    let suggestions: Vec<&str> = scored
+        .into_iter()
+        .take(MAX_SUGGESTIONS)
+        .map(|(_, name)| name)
+        .collect();
+
+    let mut message = format!("VPS with name or ID '{}' not found", query);
+    if !suggestions.is_empty() {
+        message.push_str(&format!(". Did you mean: {}?", suggestions.join(", ")));
+    } else if query.len() < MIN_PREFIX_LEN && query.chars().all(|c| c.is_ascii_hexdigit()) {
+        message.push_str(&format!(
+            " (ID prefixes need at least {} characters)",
+            MIN_PREFIX_LEN
+        ));
+    }
+    VpsError::NotFound(message)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::Utc;
+
+    fn vm(name: &str, id: &str) -> VM {
+        VM {
+            id: id.to_string(),
+            name: name.to_string(),
+            description: String::new(),
+            labels: Default::default(),
+            cpu: 1,
+            memory: 512,
+            disk_size: 10,
+            image: "ubuntu-24.04".to_string(),
+            status: "running".to_string(),
+            ip_address: String::new(),
+            created_at: Utc::now(),
+            socket_path: String::new(),
+            kernel_path: String::new(),
+            rootfs_path: String::new(),
+            tap_device: String::new(),
+        }
+    }
+
+    fn fleet() -> Vec<VM> {
+        vec![
+            vm("web-1", "3f2a9c10-0000-4000-8000-000000000001"),
+            vm("web-2", "3f2a7b20-0000-4000-8000-000000000002"),
+            vm("db-1", "a1b2c3d4-0000-4000-8000-000000000003"),
+            // A name that is also a prefix of another VPS's ID
+            vm("a1b2", "9e8d7c6b-0000-4000-8000-000000000004"),
+        ]
+    }
+
+    fn names(query: &str) -> Vec<String> {
+        matching(fleet(), query)
+            .unwrap()
+            .into_iter()
+            .map(|vm| vm.name)
+            .collect()
+    }
+
+    #[test]
+    fn matches_exact_name_or_id() {
+        assert_eq!(names("web-1"), ["web-1"]);
+        assert_eq!(names("a1b2c3d4-0000-4000-8000-000000000003"), ["db-1"]);
+    }
+
+    #[test]
+    fn exact_name_wins_over_id_prefix() {
+        assert_eq!(names("a1b2"), ["a1b2"]);
+    }
+
+    #[test]
+    fn matches_id_prefix() {
+        assert_eq!(names("a1b2c3"), ["db-1"]);
+        assert_eq!(names("3F2A9C"), ["web-1"]);
+    }
+
+    #[test]
+    fn short_prefix_is_not_an_id() {
+        assert!(matches!(
+            matching(fleet(), "3f2"),
+            Err(VpsError::NotFound(_))
+        ));
+    }
+
+    #[test]
+    fn glob_may_match_several() {
+        assert_eq!(names("web-*"), ["web-1", "web-2"]);
+        assert_eq!(names("db-?"), ["db-1"]);
+    }
+
+    #[test]
+    fn glob_matching_nothing_is_not_found() {
+        assert!(matches!(
+            matching(fleet(), "cache-*"),
+            Err(VpsError::NotFound(_))
+        ));
+    }
+
+    #[test]
+    fn invalid_glob_is_a_validation_error() {
+        assert!(matches!(
+            matching(fleet(), "web-[1"),
+            Err(VpsError::Validation(_))
+        ));
+    }
+
+    #[test]
+    fn shared_id_prefix_is_ambiguous() {
+        match matching(fleet(), "3f2a") {
+            Err(VpsError::Ambiguous(message)) => {
+                assert!(message.contains("web-1 (3f2a9c10)"));
+                assert!(message.contains("web-2 (3f2a7b20)"));
+            }
+            other => panic!("expected an ambiguity error, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn not_found_suggests_similar_names() {
+        match matching(fleet(), "web1") {
+            Err(VpsError::NotFound(message)) => assert!(message.contains("Did you mean: web-")),
+            other => panic!("expected not found, got {:?}", other),
+        }
+    }
+}
@@ -0,0 +1,158 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::fmt;
+use std::str::FromStr;
+
+/// A VPS instance as reported by the API server.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct VM {
+    /// Server-assigned UUID.
+    pub id: String,
+    pub name: String,
+    #[serde(default)]
+    pub description: String,
+    /// Free-form key/value metadata.
+    #[serde(default)]
+    pub labels: BTreeMap<String, String>,
+    /// Number of vCPUs.
+    pub cpu: u32,
+    /// Memory in MB.
+    pub memory: u32,
+    /// Disk size in GB.
+    pub disk_size: u32,
+    /// Base image the root filesystem was built from.
+    pub image: String,
+    /// Lifecycle status, e.g. `created`, `running`, `paused` or `stopped`,
+    /// or `stopping`, `rebooting`, `snapshotting` or `restoring` while the
+    /// server works on it.
+    pub status: String,
+    pub ip_address: String,
+    pub created_at: DateTime<Utc>,
+    /// Firecracker API socket on the host.
+    pub socket_path: String,
+    pub kernel_path: String,
+    pub rootfs_path: String,
+    /// Host TAP device backing the VM's network interface.
+    pub tap_device: String,
+}
+
+/// Parameters for creating a new VPS.
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct VMRequest {
+    pub name: String,
+    /// Number of vCPUs (1-8).
+    pub cpu: u32,
+    /// Memory in MB (128-8192).
+    pub memory: u32,
+    /// Disk size in GB (1-100).
+    pub disk_size: u32,
+    pub image: String,
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub labels: BTreeMap<String, String>,
+}
+
+/// Parameters for cloning a VPS. The clone keeps the source's image, disk
+/// size and labels; CPU and memory default to the source's.
+#[derive(Serialize, Deserialize, Debug, Clone, Default)]
+pub struct CloneRequest {
+    pub name: String,
+    /// Number of vCPUs (1-8).
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub cpu: Option<u32>,
+    /// Memory in MB (128-8192).
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub memory: Option<u32>,
+    /// Copy the disk saved in this snapshot of the source instead of its
+    /// current disk.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub snapshot_id: Option<String>,
+}
+
+/// Changes to a VPS's metadata. Unset fields are kept; `labels` replaces
+/// all of the VPS's labels.
+#[derive(Serialize, Deserialize, Debug, Clone, Default)]
+pub struct UpdateRequest {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub name: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub description: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub labels: Option<BTreeMap<String, String>>,
+}
+
+/// New resources for a stopped VPS. Unset fields are kept; the disk can
+/// only grow.
+#[derive(Serialize, Deserialize, Debug, Clone, Default)]
+pub struct ResizeRequest {
+    /// Number of vCPUs (1-8).
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub cpu: Option<u32>,
+    /// Memory in MB (128-8192).
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub memory: Option<u32>,
+    /// Disk size in GB (1-100).
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub disk_size: Option<u32>,
+}
+
+/// A point-in-time copy of a VPS's memory, VM state and disk.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct Snapshot {
+    /// Server-assigned UUID.
+    pub id: String,
+    /// ID of the VPS the snapshot was taken of.
+    pub vm_id: String,
+    #[serde(rename = "type")]
+    pub snapshot_type: SnapshotType,
+    /// Size of the snapshot files in bytes.
+    pub size: u64,
+    pub created_at: DateTime<Utc>,
+}
+
+/// What a snapshot's memory file holds.
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq, Default)]
+#[serde(rename_all = "lowercase")]
+pub enum SnapshotType {
+    /// All guest memory.
+    #[default]
+    Full,
+    /// Only the memory pages changed since the VPS's previous snapshot.
+    Diff,
+}
+
+impl fmt::Display for SnapshotType {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            SnapshotType::Full => f.write_str("full"),
+            SnapshotType::Diff => f.write_str("diff"),
+        }
+    }
+}
+
+impl FromStr for SnapshotType {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "full" => Ok(SnapshotType::Full),
+            "diff" => Ok(SnapshotType::Diff),
+            _ => Err(format!("'{}' is not a snapshot type; use full or diff", s)),
+        }
+    }
+}
+
+/// Parameters for taking a snapshot.
+#[derive(Serialize, Deserialize, Debug, Clone, Default)]
+pub struct SnapshotRequest {
+    #[serde(rename = "type")]
+    pub snapshot_type: SnapshotType,
+}
+
+/// Envelope wrapping every API response.
+#[derive(Deserialize, Debug)]
+pub struct ApiResponse<T> {
+    pub success: bool,
+    pub message: String,
+    pub data: Option<T>,
+}
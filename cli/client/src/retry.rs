@@ -0,0 +1,78 @@
+use rand::Rng;
+use reqwest::StatusCode;
+use std::time::Duration;
+
+use crate::error::{TimeoutPhase, VpsError};
+
+/// How [`VPSClient`](crate::VPSClient) retries failed requests.
+///
+/// Delays grow exponentially from `initial_backoff` up to `max_backoff`.
+/// With `jitter` enabled each delay is drawn uniformly from the upper half
+/// of that window, so clients that failed together don't retry together.
+#[derive(Debug, Clone)]
+pub struct RetryPolicy {
+    /// Total attempts per request, including the first one.
+    pub max_attempts: u32,
+    pub initial_backoff: Duration,
+    pub max_backoff: Duration,
+    pub jitter: bool,
+}
+
+impl Default for RetryPolicy {
+    fn default() -> Self {
+        Self {
+            max_attempts: 3,
+            initial_backoff: Duration::from_millis(200),
+            max_backoff: Duration::from_secs(5),
+            jitter: true,
+        }
+    }
+}
+
+impl RetryPolicy {
+    /// A policy that sends every request exactly once.
+    pub fn none() -> Self {
+        Self {
+            max_attempts: 1,
+            ..Self::default()
+        }
+    }
+
+    /// Delay before retry number `retry` (starting at 1).
+    pub fn backoff(&self, retry: u32) -> Duration {
+        let exponent = retry.saturating_sub(1).min(16);
+        let delay = self
+            .initial_backoff
+            .saturating_mul(1 << exponent)
+            .min(self.max_backoff);
+
+        if self.jitter {
+            delay.mul_f64(rand::rng().random_range(0.5..=1.0))
+        } else {
+            delay
+        }
+    }
+}
+
+/// Whether a request that failed with `err` may be sent again.
+///
+/// Connection failures are always retried since the request never reached
+/// the server. Resets, timeouts and gateway errors are only retried when
+/// repeating the request can't cause a second side effect.
+pub(crate) fn is_retryable(err: &VpsError, idempotent: bool) -> bool {
+    match err {
+        VpsError::Transport(e) => e.is_connect() || idempotent,
+        VpsError::Timeout { phase, .. } => *phase == TimeoutPhase::Connect || idempotent,
+        VpsError::Http { status, .. } => {
+            idempotent
+                && matches!(
+                    *status,
+                    StatusCode::TOO_MANY_REQUESTS
+                        | StatusCode::BAD_GATEWAY
+                        | StatusCode::SERVICE_UNAVAILABLE
+                        | StatusCode::GATEWAY_TIMEOUT
+                )
+        }
+        _ => false,
+    }
+}
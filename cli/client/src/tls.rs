@@ -0,0 +1,78 @@
+use reqwest::{Certificate, ClientBuilder, Identity};
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use crate::error::{Result, VpsError};
+
+/// TLS settings for talking to an HTTPS server. All files are PEM.
+#[derive(Debug, Clone, Default)]
+pub struct TlsOptions {
+    /// CA bundle used to verify the server certificate, in addition to the
+    /// system roots. May hold several certificates.
+    pub ca_cert: Option<PathBuf>,
+    /// Client certificate for mutual TLS. Requires `client_key`.
+    pub client_cert: Option<PathBuf>,
+    /// PKCS#8 private key (`BEGIN PRIVATE KEY`) for `client_cert`.
+    pub client_key: Option<PathBuf>,
+    /// Accept any server certificate. Only for testing: this disables all
+    /// protection against man-in-the-middle attacks.
+    pub insecure_skip_verify: bool,
+}
+
+impl TlsOptions {
+    pub(crate) fn apply(&self, mut builder: ClientBuilder) -> Result<ClientBuilder> {
+        if let Some(path) = &self.ca_cert {
+            let bundle = read_pem(path, "CA bundle")?;
+            let certs = Certificate::from_pem_bundle(&bundle).map_err(|e| {
+                VpsError::Config(format!("Invalid CA bundle {}: {}", path.display(), e))
+            })?;
+            if certs.is_empty() {
+                return Err(VpsError::Config(format!(
+                    "CA bundle {} contains no certificates",
+                    path.display()
+                )));
+            }
+            for cert in certs {
+                builder = builder.add_root_certificate(cert);
+            }
+        }
+
+        match (&self.client_cert, &self.client_key) {
+            (Some(cert_path), Some(key_path)) => {
+                let cert = read_pem(cert_path, "client certificate")?;
+                let key = read_pem(key_path, "client key")?;
+                let identity = Identity::from_pkcs8_pem(&cert, &key).map_err(|e| {
+                    VpsError::Config(format!(
+                        "Invalid client certificate {} or key {} (the key must be PKCS#8): {}",
+                        cert_path.display(),
+                        key_path.display(),
+                        e
+                    ))
+                })?;
+                builder = builder.identity(identity);
+            }
+            (Some(_), None) => {
+                return Err(VpsError::Config(
+                    "A client certificate needs a client key".to_string(),
+                ))
+            }
+            (None, Some(_)) => {
+                return Err(VpsError::Config(
+                    "A client key needs a client certificate".to_string(),
+                ))
+            }
+            (None, None) => {}
+        }
+
+        if self.insecure_skip_verify {
+            builder = builder.danger_accept_invalid_certs(true);
+        }
+
+        Ok(builder)
+    }
+}
+
+fn read_pem(path: &Path, what: &str) -> Result<Vec<u8>> {
+    fs::read(path)
+        .map_err(|e| VpsError::Config(format!("Cannot read {} {}: {}", what, path.display(), e)))
+}
@@ -0,0 +1,109 @@
+use fc_vps_client::{RetryPolicy, VPSClient, VpsError};
+use mockito::Server;
+use reqwest::StatusCode;
+use std::time::Duration;
+
+fn client(server: &Server) -> VPSClient {
+    VPSClient::builder(server.url())
+        .retry_policy(RetryPolicy {
+            max_attempts: 3,
+            initial_backoff: Duration::from_millis(1),
+            max_backoff: Duration::from_millis(1),
+            jitter: false,
+        })
+        .build()
+        .unwrap()
+}
+
+#[tokio::test]
+async fn plain_text_404_keeps_status_and_body() {
+    let mut server = Server::new_async().await;
+    server
+        .mock("GET", "/api/v1/vms/abc")
+        .with_status(404)
+        .with_header("content-type", "text/plain")
+        .with_body("404 page not found")
+        .create_async()
+        .await;
+
+    match client(&server).get_vm("abc").await {
+        Err(VpsError::Http { status, body }) => {
+            assert_eq!(status, StatusCode::NOT_FOUND);
+            assert_eq!(body, "404 page not found");
+        }
+        other => panic!("expected an HTTP error, got {:?}", other),
+    }
+}
+
+#[tokio::test]
+async fn html_502_is_an_http_error_retried_for_get() {
+    let mut server = Server::new_async().await;
+    let mock = server
+        .mock("GET", "/api/v1/vms")
+        .with_status(502)
+        .with_header("content-type", "text/html")
+        .with_body("<html><body><h1>502 Bad Gateway</h1></body></html>")
+        .expect(3)
+        .create_async()
+        .await;
+
+    match client(&server).list_vms().await {
+        Err(VpsError::Http { status, .. }) => assert_eq!(status, StatusCode::BAD_GATEWAY),
+        other => panic!("expected an HTTP error, got {:?}", other),
+    }
+    mock.assert_async().await;
+}
+
+#[tokio::test]
+async fn html_502_is_not_retried_for_post() {
+    let mut server = Server::new_async().await;
+    let mock = server
+        .mock("POST", "/api/v1/vms/abc/start")
+        .with_status(502)
+        .with_header("content-type", "text/html")
+        .with_body("<html><body><h1>502 Bad Gateway</h1></body></html>")
+        .expect(1)
+        .create_async()
+        .await;
+
+    match client(&server).start_vm("abc").await {
+        Err(VpsError::Http { status, .. }) => assert_eq!(status, StatusCode::BAD_GATEWAY),
+        other => panic!("expected an HTTP error, got {:?}", other),
+    }
+    mock.assert_async().await;
+}
+
+#[tokio::test]
+async fn failed_envelope_is_classified_by_status() {
+    let mut server = Server::new_async().await;
+    server
+        .mock("POST", "/api/v1/vms/abc/start")
+        .with_status(409)
+        .with_header("content-type", "application/json")
+        .with_body(r#"{"success":false,"message":"Failed to start VM: VM is already running"}"#)
+        .create_async()
+        .await;
+    let mock = server
+        .mock("POST", "/api/v1/vms/abc/stop")
+        .with_status(500)
+        .with_header("content-type", "application/json")
+        .with_body(r#"{"success":false,"message":"Failed to stop VM: VM is not running"}"#)
+        .expect(1)
+        .create_async()
+        .await;
+
+    let client = client(&server);
+    match client.start_vm("abc").await {
+        Err(VpsError::Conflict(message)) => {
+            assert!(message.contains("VM is already running"));
+            assert!(message.contains("409"));
+        }
+        other => panic!("expected a conflict, got {:?}", other),
+    }
+    // A 500 is a server error whatever it says, and not retried
+    match client.stop_vm("abc").await {
+        Err(VpsError::Api(message)) => assert!(message.contains("not running")),
+        other => panic!("expected an API error, got {:?}", other),
+    }
+    mock.assert_async().await;
+}
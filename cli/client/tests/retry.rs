@@ -0,0 +1,179 @@
+use fc_vps_client::{RetryPolicy, VMRequest, VPSClient, VpsError};
+use mockito::Server;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+use tokio::io::AsyncReadExt;
+use tokio::net::TcpListener;
+
+const VM_JSON: &str = r#"{
+    "id": "3f2a9c10-0000-4000-8000-000000000001",
+    "name": "web-1",
+    "cpu": 1,
+    "memory": 512,
+    "disk_size": 10,
+    "image": "ubuntu-24.04",
+    "status": "created",
+    "ip_address": "172.16.0.10",
+    "created_at": "2026-01-02T03:04:05Z",
+    "socket_path": "/tmp/web-1.sock",
+    "kernel_path": "/var/lib/fc-vps/vmlinux",
+    "rootfs_path": "/var/lib/fc-vps/web-1.ext4",
+    "tap_device": "tap0"
+}"#;
+
+fn client(url: String) -> VPSClient {
+    VPSClient::builder(url)
+        .retry_policy(RetryPolicy {
+            max_attempts: 3,
+            initial_backoff: Duration::from_millis(1),
+            max_backoff: Duration::from_millis(1),
+            jitter: false,
+        })
+        .build()
+        .unwrap()
+}
+
+/// A server that reads each request and closes the connection without
+/// answering. Returns its URL and the number of requests it received.
+async fn resetting_server() -> (String, Arc<AtomicUsize>) {
+    let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let url = format!("http://{}", listener.local_addr().unwrap());
+    let hits = Arc::new(AtomicUsize::new(0));
+
+    let counter = hits.clone();
+    tokio::spawn(async move {
+        loop {
+            let (mut socket, _) = listener.accept().await.unwrap();
+            let mut buf = [0; 4096];
+            let _ = socket.read(&mut buf).await;
+            counter.fetch_add(1, Ordering::SeqCst);
+        }
+    });
+
+    (url, hits)
+}
+
+#[tokio::test]
+async fn post_is_not_retried_after_reset() {
+    let (url, hits) = resetting_server().await;
+
+    let result = client(url).start_vm("abc").await;
+
+    assert!(
+        matches!(result, Err(VpsError::Transport(_))),
+        "{:?}",
+        result
+    );
+    assert_eq!(hits.load(Ordering::SeqCst), 1);
+}
+
+#[tokio::test]
+async fn get_is_retried_after_reset() {
+    let (url, hits) = resetting_server().await;
+
+    let result = client(url).list_vms().await;
+
+    assert!(
+        matches!(result, Err(VpsError::Transport(_))),
+        "{:?}",
+        result
+    );
+    assert_eq!(hits.load(Ordering::SeqCst), 3);
+}
+
+#[tokio::test]
+async fn create_keeps_idempotency_key_across_attempts() {
+    let mut server = Server::new_async().await;
+    let keys = Arc::new(Mutex::new(Vec::new()));
+
+    let seen = keys.clone();
+    let failing = server
+        .mock("POST", "/api/v1/vms")
+        .with_status(503)
+        .with_body_from_request(move |request| {
+            let key = request.header("Idempotency-Key")[0].to_str().unwrap();
+            seen.lock().unwrap().push(key.to_string());
+            b"Service Unavailable".to_vec()
+        })
+        .expect(2)
+        .create_async()
+        .await;
+    let seen = keys.clone();
+    let succeeding = server
+        .mock("POST", "/api/v1/vms")
+        .with_status(201)
+        .with_header("content-type", "application/json")
+        .with_body_from_request(move |request| {
+            let key = request.header("Idempotency-Key")[0].to_str().unwrap();
+            seen.lock().unwrap().push(key.to_string());
+            format!(
+                r#"{{"success":true,"message":"VM created","data":{}}}"#,
+                VM_JSON
+            )
+            .into()
+        })
+        .expect(1)
+        .create_async()
+        .await;
+
+    let vm = client(server.url())
+        .create_vm(VMRequest {
+            name: "web-1".to_string(),
+            cpu: 1,
+            memory: 512,
+            disk_size: 10,
+            image: "ubuntu-24.04".to_string(),
+            labels: Default::default(),
+        })
+        .await
+        .unwrap();
+
+    assert_eq!(vm.name, "web-1");
+    failing.assert_async().await;
+    succeeding.assert_async().await;
+    let keys = keys.lock().unwrap();
+    assert_eq!(keys.len(), 3);
+    assert!(keys.iter().all(|key| *key == keys[0]));
+}
+
+#[tokio::test]
+async fn retried_delete_that_finds_nothing_succeeds() {
+    let mut server = Server::new_async().await;
+    let gateway = server
+        .mock("DELETE", "/api/v1/vms/abc")
+        .with_status(502)
+        .with_body("Bad Gateway")
+        .expect(1)
+        .create_async()
+        .await;
+    let gone = server
+        .mock("DELETE", "/api/v1/vms/abc")
+        .with_status(404)
+        .with_header("content-type", "application/json")
+        .with_body(r#"{"success":false,"message":"VM not found"}"#)
+        .expect(1)
+        .create_async()
+        .await;
+
+    client(server.url()).delete_vm("abc").await.unwrap();
+
+    gateway.assert_async().await;
+    gone.assert_async().await;
+}
+
+#[tokio::test]
+async fn first_delete_that_finds_nothing_fails() {
+    let mut server = Server::new_async().await;
+    server
+        .mock("DELETE", "/api/v1/vms/abc")
+        .with_status(404)
+        .with_header("content-type", "application/json")
+        .with_body(r#"{"success":false,"message":"VM not found"}"#)
+        .create_async()
+        .await;
+
+    let result = client(server.url()).delete_vm("abc").await;
+
+    assert!(matches!(result, Err(VpsError::NotFound(_))), "{:?}", result);
+}
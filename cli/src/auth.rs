@@ -0,0 +1,87 @@
+use anyhow::{Context, Result};
+use clap::ValueEnum;
+use fc_vps_client::Auth;
+use serde::{Deserialize, Serialize};
+use std::process::Command;
+
+use crate::config::ContextConfig;
+
+/// How the API token is presented to the server.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, ValueEnum, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum AuthScheme {
+    /// `Authorization: Bearer <token>`
+    #[default]
+    Bearer,
+    /// `X-API-Key: <token>`
+    ApiKey,
+}
+
+impl AuthScheme {
+    fn auth(self, token: String) -> Auth {
+        match self {
+            AuthScheme::Bearer => Auth::Bearer(token),
+            AuthScheme::ApiKey => Auth::ApiKey(token),
+        }
+    }
+}
+
+/// Picks the credentials for this run. Flags and env vars win over the
+/// context; within each, a literal token wins over a credential helper.
+pub fn resolve(
+    token: Option<String>,
+    token_command: Option<String>,
+    scheme: Option<AuthScheme>,
+    context: Option<&ContextConfig>,
+) -> Result<Option<Auth>> {
+    let scheme = scheme
+        .or_else(|| context.and_then(|c| c.auth_scheme))
+        .unwrap_or_default();
+
+    let token = match (token, token_command) {
+        (Some(token), _) => Some(token),
+        (None, Some(command)) => Some(run_credential_helper(&command)?),
+        (None, None) => match context {
+            Some(ContextConfig {
+                token: Some(token), ..
+            }) => Some(token.clone()),
+            Some(ContextConfig {
+                token_command: Some(command),
+                ..
+            }) => Some(run_credential_helper(command)?),
+            _ => None,
+        },
+    };
+
+    Ok(token.map(|token| scheme.auth(token)))
+}
+
+/// Runs `command` through the shell and uses its trimmed stdout as the
+/// token, so secrets can come from a password manager or cloud CLI.
+fn run_credential_helper(command: &str) -> Result<String> {
+    let output = if cfg!(windows) {
+        Command::new("cmd").args(["/C", command]).output()
+    } else {
+        Command::new("sh").args(["-c", command]).output()
+    }
+    .with_context(|| format!("Failed to run credential helper '{}'", command))?;
+
+    if !output.status.success() {
+        anyhow::bail!(
+            "Credential helper '{}' failed ({}): {}",
+            command,
+            output.status,
+            String::from_utf8_lossy(&output.stderr).trim()
+        );
+    }
+
+    let token = String::from_utf8(output.stdout)
+        .context("Credential helper printed a token that is not valid UTF-8")?
+        .trim()
+        .to_string();
+    if token.is_empty() {
+        anyhow::bail!("Credential helper '{}' printed no token", command);
+    }
+
+    Ok(token)
+}
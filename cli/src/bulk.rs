@@ -0,0 +1,356 @@
+use anyhow::Result;
+use clap::Args;
+use colored::*;
+use dialoguer::Confirm;
+use fc_vps_client::{VPSClient, VpsError, VM};
+use futures::stream::{self, StreamExt};
+use indicatif::{MultiProgress, ProgressBar};
+
+use crate::filter::Selector;
+use crate::output::{
+    format_status, print_vm, print_vms, say, spinner, spinner_in, spinner_with, OutputFormat,
+};
+use crate::readiness::{self, ReadyArgs};
+
+/// Selects several VPS instances for the lifecycle commands, on top
+/// of the IDs and names given as arguments.
+#[derive(Args, Debug, Default)]
+pub struct BulkArgs {
+    /// Act on every VPS instance
+    #[arg(long, conflicts_with = "ids")]
+    pub all: bool,
+
+    #[command(flatten)]
+    pub selector: Selector,
+
+    /// How many VPS instances to act on at once
+    #[arg(short, long, default_value = "4", value_parser = clap::value_parser!(u16).range(1..))]
+    #[arg(env = "FC_VPS_PARALLEL")]
+    pub parallel: u16,
+}
+
+impl BulkArgs {
+    /// Whether the arguments name exactly one VPS, which keeps the detailed
+    /// single-VPS output.
+    pub fn is_single(&self, ids: &[String], targets: &[VM]) -> bool {
+        ids.len() == 1 && targets.len() == 1 && !self.all && self.selector.is_empty()
+    }
+}
+
+/// A lifecycle operation applied to each selected VPS.
+#[derive(Clone, Copy)]
+pub enum Action<'a> {
+    /// Start, then wait for readiness if given.
+    Start(Option<&'a ReadyArgs>),
+    Stop,
+    Pause,
+    Resume,
+    /// Stop if running, start, then wait for readiness.
+    Restart(&'a ReadyArgs),
+    /// In-guest reboot, then wait for readiness if given.
+    Reboot(Option<&'a ReadyArgs>),
+    Delete,
+}
+
+/// What happened to one VPS.
+enum Outcome {
+    Done(VM),
+    /// Nothing to do, e.g. the VPS was already running.
+    Skipped(VM, String),
+}
+
+impl Action<'_> {
+    fn verb(&self) -> &'static str {
+        match self {
+            Action::Start(_) => "start",
+            Action::Stop => "stop",
+            Action::Pause => "pause",
+            Action::Resume => "resume",
+            Action::Restart(_) => "restart",
+            Action::Reboot(_) => "reboot",
+            Action::Delete => "delete",
+        }
+    }
+
+    fn past(&self) -> &'static str {
+        match self {
+            Action::Start(_) => "started",
+            Action::Stop => "stopped",
+            Action::Pause => "paused",
+            Action::Resume => "resumed",
+            Action::Restart(_) => "restarted",
+            Action::Reboot(_) => "rebooted",
+            Action::Delete => "deleted",
+        }
+    }
+
+    fn progressive(&self) -> &'static str {
+        match self {
+            Action::Start(_) => "Starting",
+            Action::Stop => "Stopping",
+            Action::Pause => "Pausing",
+            Action::Resume => "Resuming",
+            Action::Restart(_) => "Restarting",
+            Action::Reboot(_) => "Rebooting",
+            Action::Delete => "Deleting",
+        }
+    }
+
+    /// Whether the action applies to `vm` as it is: `None` to go ahead, the
+    /// reason to skip it when the VPS is already where the action would
+    /// take it, or a [`VpsError::Conflict`] when the action can't be done
+    /// from its status. One VPS or many, the same rule applies.
+    pub fn check(&self, vm: &VM) -> Result<Option<&'static str>> {
+        let conflict = |message: &str| -> Result<Option<&'static str>> {
+            Err(VpsError::Conflict(format!("VPS '{}' {}", vm.name, message)).into())
+        };
+
+        match self {
+            Action::Start(_) if vm.status == "running" => Ok(Some("already running")),
+            Action::Stop if vm.status == "stopped" => Ok(Some("already stopped")),
+            Action::Pause if vm.status == "paused" => Ok(Some("already paused")),
+            Action::Pause if vm.status != "running" => conflict("is not running"),
+            Action::Resume if vm.status == "running" => Ok(Some("already running")),
+            Action::Resume if vm.status != "paused" => {
+                conflict("is not paused; use `fc-vps start` instead")
+            }
+            Action::Reboot(_) if vm.status != "running" => {
+                conflict("is not running; use `fc-vps start` instead")
+            }
+            _ => Ok(None),
+        }
+    }
+
+    async fn apply(&self, client: &VPSClient, vm: &VM, pb: &ProgressBar) -> Result<Outcome> {
+        if let Some(reason) = self.check(vm)? {
+            // Still make sure it is reachable, so `start --wait` can gate scripts
+            if let Action::Start(Some(ready)) = self {
+                readiness::wait_until_ready(client, vm, ready, pb).await?;
+            }
+            return Ok(Outcome::Skipped(vm.clone(), reason.to_string()));
+        }
+
+        match self {
+            Action::Start(wait) => {
+                client.start_vm(&vm.id).await?;
+                if let Some(ready) = wait {
+                    readiness::wait_until_ready(client, vm, ready, pb).await?;
+                }
+            }
+            Action::Stop => client.stop_vm(&vm.id).await?,
+            Action::Pause => client.pause_vm(&vm.id).await?,
+            Action::Resume => client.resume_vm(&vm.id).await?,
+            Action::Restart(ready) => {
+                if vm.status == "running" || vm.status == "paused" {
+                    pb.set_message(format!("{}: stop...", vm.name));
+                    client.stop_vm(&vm.id).await?;
+                }
+                pb.set_message(format!("{}: start...", vm.name));
+                client.start_vm(&vm.id).await?;
+                readiness::wait_until_ready(client, vm, ready, pb).await?;
+            }
+            Action::Reboot(wait) => {
+                client.reboot_vm(&vm.id).await?;
+                if let Some(ready) = wait {
+                    readiness::wait_until_ready(client, vm, ready, pb).await?;
+                }
+            }
+            Action::Delete => {
+                client.delete_vm(&vm.id).await?;
+                // The VPS as it was just before deletion
+                return Ok(Outcome::Done(vm.clone()));
+            }
+        }
+
+        Ok(Outcome::Done(client.get_vm(&vm.id).await?))
+    }
+}
+
+/// Applies `action` to a single VPS with a spinner, leaving the detailed
+/// output to the caller. Returns the VPS as updated, or `None` when there
+/// was nothing to do, after saying why.
+pub async fn run_one(
+    client: &VPSClient,
+    action: Action<'_>,
+    vm: &VM,
+    output: OutputFormat,
+) -> Result<Option<VM>> {
+    if action.check(vm)?.is_none() {
+        say!(output, "{} VPS '{}'...", action.progressive(), vm.name);
+    }
+
+    let pb = match action {
+        Action::Stop | Action::Delete => spinner_with("{spinner:.red} {msg}"),
+        _ => spinner(),
+    };
+    pb.set_message(format!("{} VM...", action.progressive()));
+
+    match action.apply(client, vm, &pb).await {
+        Ok(Outcome::Done(vm)) => {
+            pb.finish_with_message(format!("✅ VPS {} successfully!", action.past()));
+            Ok(Some(vm))
+        }
+        Ok(Outcome::Skipped(vm, reason)) => {
+            pb.finish_and_clear();
+            say!(
+                output,
+                "{}",
+                format!("VPS '{}' is {}", vm.name, reason).yellow()
+            );
+            if output.is_structured() {
+                print_vm(&vm, output)?;
+            }
+            Ok(None)
+        }
+        Err(e) => {
+            pb.finish_with_message(format!("❌ Failed to {} VPS", action.verb()));
+            Err(e)
+        }
+    }
+}
+
+/// Resolves the IDs, names and patterns in `ids`, or every VPS for `--all`
+/// and a bare selector, and keeps those matching the selector. Each VPS is
+/// listed once, in the order given.
+pub async fn select(client: &VPSClient, ids: &[String], args: &BulkArgs) -> Result<Vec<VM>> {
+    let candidates = if ids.is_empty() {
+        client.list_vms().await?
+    } else {
+        let mut vms: Vec<VM> = Vec::new();
+        for id in ids {
+            for vm in client.find_vms_by_name_or_id(id).await? {
+                if !vms.iter().any(|seen| seen.id == vm.id) {
+                    vms.push(vm);
+                }
+            }
+        }
+        vms
+    };
+
+    let mut selected = Vec::with_capacity(candidates.len());
+    for vm in candidates {
+        if args.selector.matches(&vm)? {
+            selected.push(vm);
+        }
+    }
+    Ok(selected)
+}
+
+/// Applies `action` to every VPS, at most `parallel` at a time, and prints
+/// one result line per VPS. Fails if any of them failed.
+pub async fn run(
+    client: &VPSClient,
+    action: Action<'_>,
+    vms: Vec<VM>,
+    parallel: u16,
+    force: bool,
+    output: OutputFormat,
+) -> Result<()> {
+    if vms.is_empty() {
+        say!(output, "{}", "No VPS instances matched".yellow());
+        return Ok(());
+    }
+
+    if !force {
+        if let Action::Delete = action {
+            say!(
+                output,
+                "{}",
+                "⚠️  WARNING: This action cannot be undone!".red().bold()
+            );
+        }
+        say!(
+            output,
+            "The following {} VPS instances will be {}:",
+            vms.len(),
+            action.past()
+        );
+        for vm in &vms {
+            say!(
+                output,
+                "  • {} ({})",
+                vm.name.bold(),
+                format_status(&vm.status)
+            );
+        }
+        say!(output);
+
+        let confirm = Confirm::new()
+            .with_prompt(format!(
+                "Are you sure you want to {} {} VPS instances?",
+                action.verb(),
+                vms.len()
+            ))
+            .default(false)
+            .interact()?;
+
+        if !confirm {
+            say!(output, "Operation cancelled");
+            return Ok(());
+        }
+    }
+
+    let total = vms.len();
+    let multi = MultiProgress::new();
+    let results: Vec<(VM, Result<Outcome>)> = stream::iter(vms)
+        .map(|vm| {
+            let pb = spinner_in(&multi);
+            pb.set_message(format!("{}: {}...", vm.name, action.verb()));
+
+            async move {
+                let result = action.apply(client, &vm, &pb).await;
+                pb.finish_and_clear();
+                (vm, result)
+            }
+        })
+        // Ordered, so the summary follows the selection
+        .buffered(parallel.into())
+        .collect()
+        .await;
+
+    let mut done = Vec::new();
+    let mut skipped = 0;
+    let mut failures = Vec::new();
+    for (vm, result) in results {
+        match result {
+            Ok(Outcome::Done(vm)) => {
+                say!(output, "✅ {} {}", vm.name.bold(), action.past().green());
+                done.push(vm);
+            }
+            Ok(Outcome::Skipped(vm, reason)) => {
+                say!(output, "➖ {} {}", vm.name.bold(), reason.yellow());
+                skipped += 1;
+                done.push(vm);
+            }
+            Err(e) => {
+                say!(output, "❌ {}: {:#}", vm.name.bold(), e);
+                failures.push(e);
+            }
+        }
+    }
+
+    say!(output);
+    say!(
+        output,
+        "{} {}, {} skipped, {} failed",
+        done.len() - skipped,
+        action.past(),
+        skipped,
+        failures.len()
+    );
+
+    if output.is_structured() {
+        print_vms(&done, output)?;
+    }
+
+    let failed = failures.len();
+    match failures.into_iter().next() {
+        None => Ok(()),
+        Some(e) => Err(e.context(format!(
+            "{} of {} VPS instances failed to {}",
+            failed,
+            total,
+            action.verb()
+        ))),
+    }
+}
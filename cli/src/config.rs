@@ -0,0 +1,207 @@
+use anyhow::{Context, Result};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::fs;
+use std::io::Write;
+use std::path::{Path, PathBuf};
+
+use crate::auth::AuthScheme;
+
+/// The `fc-vps` config file: named server contexts, kubeconfig style.
+///
+/// ```toml
+/// current_context = "prod-1"
+///
+/// [contexts.prod-1]
+/// server = "https://vps1.example.com"
+/// token_command = "pass show fc-vps/prod-1"
+///
+/// [contexts.prod-1.tls]
+/// ca_cert = "/etc/fc-vps/ca.pem"
+///
+/// [contexts.prod-1.defaults]
+/// cpu = 2
+/// memory = 1024
+/// image = "ubuntu-24.04"
+///
+/// [templates]
+/// ssh = "templates/ssh_config.hbs"
+/// ```
+#[derive(Serialize, Deserialize, Debug, Default)]
+pub struct Config {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub current_context: Option<String>,
+
+    #[serde(default)]
+    pub contexts: BTreeMap<String, ContextConfig>,
+
+    /// Named `--template @name` files shared by all contexts.
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub templates: BTreeMap<String, PathBuf>,
+}
+
+/// Everything needed to talk to one VPS server.
+#[derive(Serialize, Deserialize, Debug, Clone, Default)]
+pub struct ContextConfig {
+    pub server: String,
+
+    /// API token sent with every request.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub token: Option<String>,
+
+    /// Credential helper whose output is used as the token when `token` is
+    /// not set.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub token_command: Option<String>,
+
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub auth_scheme: Option<AuthScheme>,
+
+    #[serde(default, skip_serializing_if = "TlsConfig::is_empty")]
+    pub tls: TlsConfig,
+
+    /// Defaults for `fc-vps create` when the flag is not given.
+    #[serde(default, skip_serializing_if = "CreateDefaults::is_empty")]
+    pub defaults: CreateDefaults,
+
+    /// Named `--template @name` files, taking precedence over the shared
+    /// ones.
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub templates: BTreeMap<String, PathBuf>,
+}
+
+/// TLS settings for HTTPS servers. Paths point at PEM files.
+#[derive(Serialize, Deserialize, Debug, Clone, Default)]
+pub struct TlsConfig {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub ca_cert: Option<PathBuf>,
+
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub client_cert: Option<PathBuf>,
+
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub client_key: Option<PathBuf>,
+
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub insecure_skip_verify: bool,
+}
+
+impl TlsConfig {
+    /// The settings with relative paths taken from `dir`, the directory of
+    /// the config file, like `--template @name` files.
+    pub fn relative_to(&self, dir: &Path) -> Self {
+        let resolve = |path: &Option<PathBuf>| path.as_ref().map(|path| dir.join(path));
+        Self {
+            ca_cert: resolve(&self.ca_cert),
+            client_cert: resolve(&self.client_cert),
+            client_key: resolve(&self.client_key),
+            insecure_skip_verify: self.insecure_skip_verify,
+        }
+    }
+
+    fn is_empty(&self) -> bool {
+        self.ca_cert.is_none()
+            && self.client_cert.is_none()
+            && self.client_key.is_none()
+            && !self.insecure_skip_verify
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Default)]
+pub struct CreateDefaults {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub cpu: Option<u32>,
+
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub memory: Option<u32>,
+
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub disk: Option<u32>,
+
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub image: Option<String>,
+}
+
+impl CreateDefaults {
+    fn is_empty(&self) -> bool {
+        self.cpu.is_none() && self.memory.is_none() && self.disk.is_none() && self.image.is_none()
+    }
+}
+
+/// `$XDG_CONFIG_HOME/fc-vps/config.toml`, falling back to
+/// `~/.config/fc-vps/config.toml`.
+pub fn default_path() -> Result<PathBuf> {
+    let config_home = match std::env::var_os("XDG_CONFIG_HOME") {
+        Some(dir) if !dir.is_empty() => PathBuf::from(dir),
+        _ => dirs::home_dir()
+            .context("Cannot determine home directory; use --config")?
+            .join(".config"),
+    };
+
+    Ok(config_home.join("fc-vps").join("config.toml"))
+}
+
+impl Config {
+    /// Reads the config at `path`. A missing file is an empty config.
+    pub fn load(path: &Path) -> Result<Self> {
+        let contents = match fs::read_to_string(path) {
+            Ok(contents) => contents,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(Self::default()),
+            Err(e) => return Err(e).with_context(|| format!("Failed to read {}", path.display())),
+        };
+
+        toml::from_str(&contents).with_context(|| format!("Failed to parse {}", path.display()))
+    }
+
+    /// Writes the config to `path`, creating parent directories. The file
+    /// may hold tokens, so it is only readable by the owner: the config is
+    /// written to a new owner-only file next to it, which then replaces it.
+    pub fn save(&self, path: &Path) -> Result<()> {
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent)
+                .with_context(|| format!("Failed to create {}", parent.display()))?;
+        }
+
+        let contents = toml::to_string_pretty(self).context("Failed to serialize config")?;
+
+        let mut tmp_name = path.file_name().unwrap_or_default().to_os_string();
+        tmp_name.push(".tmp");
+        let tmp = path.with_file_name(tmp_name);
+        // A leftover from an interrupted save may have other permissions
+        let _ = fs::remove_file(&tmp);
+
+        let mut options = fs::OpenOptions::new();
+        options.write(true).create_new(true);
+        #[cfg(unix)]
+        {
+            use std::os::unix::fs::OpenOptionsExt;
+            options.mode(0o600);
+        }
+        let written = options.open(&tmp).and_then(|mut file| {
+            file.write_all(contents.as_bytes())?;
+            file.sync_all()
+        });
+        if let Err(e) = written.and_then(|()| fs::rename(&tmp, path)) {
+            let _ = fs::remove_file(&tmp);
+            return Err(e).with_context(|| format!("Failed to write {}", path.display()));
+        }
+
+        Ok(())
+    }
+
+    /// Picks the context named `name`, or the current context when no name
+    /// is given. Returns `None` when neither is set.
+    pub fn resolve(&self, name: Option<&str>) -> Result<Option<(&str, &ContextConfig)>> {
+        let Some(name) = name.or(self.current_context.as_deref()) else {
+            return Ok(None);
+        };
+
+        match self.contexts.get_key_value(name) {
+            Some((name, context)) => Ok(Some((name.as_str(), context))),
+            None => anyhow::bail!(
+                "Context '{}' not found. Run 'fc-vps context list' to see available contexts",
+                name
+            ),
+        }
+    }
+}
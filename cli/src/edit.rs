@@ -0,0 +1,153 @@
+use anyhow::{Context, Result};
+use colored::*;
+use dialoguer::{Confirm, Editor};
+use fc_vps_client::{UpdateRequest, VPSClient, VM};
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, BTreeSet};
+
+use crate::output::{print_vm, say, short_id, OutputFormat};
+
+/// The fields of a VPS that can be changed after it is created.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+#[serde(deny_unknown_fields)]
+struct Metadata {
+    name: String,
+    #[serde(default)]
+    description: String,
+    #[serde(default)]
+    labels: BTreeMap<String, String>,
+}
+
+impl From<&VM> for Metadata {
+    fn from(vm: &VM) -> Self {
+        Self {
+            name: vm.name.clone(),
+            description: vm.description.clone(),
+            labels: vm.labels.clone(),
+        }
+    }
+}
+
+impl Metadata {
+    /// The update that turns `self` into `edited`, with only the fields
+    /// that differ.
+    fn changes(&self, edited: &Metadata) -> UpdateRequest {
+        UpdateRequest {
+            name: (edited.name != self.name).then(|| edited.name.clone()),
+            description: (edited.description != self.description)
+                .then(|| edited.description.clone()),
+            labels: (edited.labels != self.labels).then(|| edited.labels.clone()),
+        }
+    }
+
+    /// One `-`/`+` line per changed value, labels key by key.
+    fn diff(&self, edited: &Metadata) -> Vec<String> {
+        let mut lines = Vec::new();
+        let mut field = |name: &str, old: Option<&String>, new: Option<&String>| {
+            if old == new {
+                return;
+            }
+            if let Some(old) = old {
+                lines.push(format!("- {}: {}", name, old).red().to_string());
+            }
+            if let Some(new) = new {
+                lines.push(format!("+ {}: {}", name, new).green().to_string());
+            }
+        };
+
+        field("name", Some(&self.name), Some(&edited.name));
+        field(
+            "description",
+            Some(&self.description).filter(|d| !d.is_empty()),
+            Some(&edited.description).filter(|d| !d.is_empty()),
+        );
+        let keys: BTreeSet<&String> = self.labels.keys().chain(edited.labels.keys()).collect();
+        for key in keys {
+            field(
+                &format!("labels.{}", key),
+                self.labels.get(key),
+                edited.labels.get(key),
+            );
+        }
+
+        lines
+    }
+}
+
+/// Opens the name, description and labels of a VPS as YAML in `$VISUAL` or
+/// `$EDITOR`, shows what changed and sends only those fields. Invalid YAML
+/// can be fixed in the editor again.
+pub async fn handle_edit(client: &VPSClient, id: &str, output: OutputFormat) -> Result<()> {
+    let vm = client.find_vm_by_name_or_id(id).await?;
+    let original = Metadata::from(&vm);
+
+    let header = format!(
+        "# Editing VPS '{}' ({}). Lines starting with '#' are ignored;\n\
+         # save an empty file or quit without saving to cancel.\n",
+        vm.name,
+        short_id(&vm.id)
+    );
+    let mut text = format!("{}{}", header, serde_yaml::to_string(&original)?);
+
+    let edited = loop {
+        let Some(saved) = Editor::new()
+            .extension(".yaml")
+            .edit(&text)
+            .context("Failed to run the editor")?
+        else {
+            say!(output, "Edit cancelled, no changes made");
+            return Ok(());
+        };
+
+        let content: String = saved
+            .lines()
+            .filter(|line| !line.trim_start().starts_with('#'))
+            .map(|line| format!("{}\n", line))
+            .collect();
+        if content.trim().is_empty() {
+            say!(output, "Edit cancelled, no changes made");
+            return Ok(());
+        }
+
+        match serde_yaml::from_str::<Metadata>(&content) {
+            Ok(edited) => break edited,
+            Err(e) => {
+                say!(output, "{}", format!("❌ Invalid YAML: {}", e).red());
+                let retry = Confirm::new()
+                    .with_prompt("Edit again?")
+                    .default(true)
+                    .interact()?;
+                if !retry {
+                    say!(output, "Edit cancelled, no changes made");
+                    return Ok(());
+                }
+                text = format!("{}# Error: {}\n{}", header, e, content);
+            }
+        }
+    };
+
+    let diff = original.diff(&edited);
+    if diff.is_empty() {
+        say!(output, "No changes made");
+        if output.is_structured() {
+            return print_vm(&vm, output);
+        }
+        return Ok(());
+    }
+
+    say!(output, "Changes to VPS '{}':", vm.name.bold());
+    for line in &diff {
+        say!(output, "  {}", line);
+    }
+    say!(output);
+
+    let updated = client.update_vm(&vm.id, original.changes(&edited)).await?;
+
+    if output.is_structured() {
+        return print_vm(&updated, output);
+    }
+
+    println!("✅ VPS '{}' updated", updated.name.bold());
+
+    Ok(())
+}
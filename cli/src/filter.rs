@@ -0,0 +1,362 @@
+use anyhow::Result;
+use chrono::{DateTime, FixedOffset};
+use clap::Args;
+use fc_vps_client::VM;
+use glob::Pattern;
+use serde_json::Value;
+use std::cmp::Ordering;
+use std::net::IpAddr;
+use std::str::FromStr;
+
+use crate::label::Requirement;
+use crate::output::{cell, FIELDS};
+
+/// Short names accepted wherever a `VM` field is expected.
+const ALIASES: [(&str, &str); 3] = [
+    ("created", "created_at"),
+    ("disk", "disk_size"),
+    ("ip", "ip_address"),
+];
+
+const NUMERIC_FIELDS: [&str; 3] = ["cpu", "memory", "disk_size"];
+
+/// Which VPS instances a command acts on, by status, labels and
+/// `--filter` conditions.
+#[derive(Args, Debug, Default)]
+pub struct Selector {
+    /// Filter by status
+    #[arg(short, long)]
+    pub status: Option<String>,
+
+    /// Label selector, e.g. team=payments,env!=prod,backup,!temporary
+    #[arg(
+        short = 'l',
+        long = "selector",
+        value_name = "SELECTOR",
+        value_delimiter = ','
+    )]
+    pub labels: Vec<Requirement>,
+
+    /// Filter expressions, e.g. cpu>=2,image=ubuntu-24.04,name~web-*
+    #[arg(long)]
+    pub filter: Vec<Conditions>,
+}
+
+impl Selector {
+    pub fn is_empty(&self) -> bool {
+        self.status.is_none() && self.labels.is_empty() && self.filter.is_empty()
+    }
+
+    /// Whether the VPS has `--status`, matches every label requirement and
+    /// meets every `--filter` condition.
+    pub fn matches(&self, vm: &VM) -> Result<bool> {
+        if let Some(status) = &self.status {
+            if !vm.status.eq_ignore_ascii_case(status) {
+                return Ok(false);
+            }
+        }
+        if !self.labels.iter().all(|req| req.matches(&vm.labels)) {
+            return Ok(false);
+        }
+        let value = serde_json::to_value(vm)?;
+        Ok(self
+            .filter
+            .iter()
+            .flat_map(|conditions| &conditions.0)
+            .all(|condition| condition.matches(&value)))
+    }
+}
+
+/// Which VPS instances `list` shows, and in what order.
+#[derive(Args, Debug, Default)]
+pub struct FilterArgs {
+    #[command(flatten)]
+    pub selector: Selector,
+
+    /// Sort by a field, e.g. created, memory, name
+    #[arg(long, value_parser = parse_field)]
+    pub sort_by: Option<&'static str>,
+
+    /// Reverse the order
+    #[arg(long)]
+    pub reverse: bool,
+}
+
+impl FilterArgs {
+    /// Keeps the VPS instances matching the selector, then applies
+    /// `--sort-by` and `--reverse`.
+    pub fn apply(&self, vms: &[VM]) -> Result<Vec<VM>> {
+        let mut rows = Vec::with_capacity(vms.len());
+        for vm in vms {
+            if self.selector.matches(vm)? {
+                rows.push((serde_json::to_value(vm)?, vm));
+            }
+        }
+
+        if let Some(field) = self.sort_by {
+            // Stable, so ties keep the server's order
+            rows.sort_by(|(a, _), (b, _)| compare(&a[field], &b[field]));
+        }
+        if self.reverse {
+            rows.reverse();
+        }
+
+        Ok(rows.into_iter().map(|(_, vm)| vm.clone()).collect())
+    }
+}
+
+/// One `--filter` argument: comma-separated conditions. A comma only starts
+/// a new condition when `<field><op>` follows it, so glob values such as
+/// `name~web-[a,b]*` can contain commas.
+#[derive(Clone, Debug)]
+pub struct Conditions(Vec<Condition>);
+
+impl FromStr for Conditions {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut conditions = Vec::new();
+        let mut start = 0;
+        for (comma, _) in s.match_indices(',') {
+            if starts_condition(&s[comma + 1..]) {
+                conditions.push(s[start..comma].parse()?);
+                start = comma + 1;
+            }
+        }
+        conditions.push(s[start..].parse()?);
+        Ok(Self(conditions))
+    }
+}
+
+/// Whether `s` begins with a field name followed by an operator.
+fn starts_condition(s: &str) -> bool {
+    let s = s.trim_start();
+    let field = s
+        .find(|c: char| !(c.is_ascii_alphanumeric() || c == '_'))
+        .unwrap_or(s.len());
+    field > 0 && s[field..].starts_with(['=', '!', '<', '>', '~'])
+}
+
+/// One `--filter` term: `<field><op><value>`.
+#[derive(Clone, Debug)]
+pub struct Condition {
+    field: &'static str,
+    op: Op,
+    value: String,
+}
+
+#[derive(Clone, Debug)]
+enum Op {
+    Eq,
+    Ne,
+    Lt,
+    Le,
+    Gt,
+    Ge,
+    Matches(Pattern),
+    NotMatches(Pattern),
+}
+
+impl Condition {
+    pub fn matches(&self, vm: &Value) -> bool {
+        let actual = &vm[self.field];
+        match &self.op {
+            Op::Eq => self.compare(actual) == Ordering::Equal,
+            Op::Ne => self.compare(actual) != Ordering::Equal,
+            Op::Lt => self.compare(actual) == Ordering::Less,
+            Op::Le => self.compare(actual) != Ordering::Greater,
+            Op::Gt => self.compare(actual) == Ordering::Greater,
+            Op::Ge => self.compare(actual) != Ordering::Less,
+            Op::Matches(pattern) => pattern.matches(&cell(actual)),
+            Op::NotMatches(pattern) => !pattern.matches(&cell(actual)),
+        }
+    }
+
+    /// Orders the VM's value relative to the condition's.
+    fn compare(&self, actual: &Value) -> Ordering {
+        let expected = match actual {
+            Value::Number(_) => self.value.parse().map_or(Value::Null, Value::Number),
+            _ => Value::String(self.value.clone()),
+        };
+        compare(actual, &expected)
+    }
+}
+
+impl FromStr for Condition {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let s = s.trim();
+        let split = s
+            .find(|c: char| !(c.is_ascii_alphanumeric() || c == '_'))
+            .ok_or_else(|| format!("'{}' has no operator (=, !=, <, <=, >, >=, ~, !~)", s))?;
+        let field = parse_field(&s[..split])?;
+
+        let rest = &s[split..];
+        let (op, value) = ["!=", "<=", ">=", "!~", "=", "<", ">", "~"]
+            .iter()
+            .find_map(|op| rest.strip_prefix(op).map(|value| (*op, value)))
+            .ok_or_else(|| format!("Unknown operator in '{}'", s))?;
+
+        let numeric = NUMERIC_FIELDS.contains(&field);
+        if numeric && op.ends_with('~') {
+            return Err(format!("'{}' cannot be matched with {}", field, op));
+        }
+        if numeric && value.parse::<u64>().is_err() {
+            return Err(format!("'{}' needs a number, got '{}'", field, value));
+        }
+
+        let op = match op {
+            "=" => Op::Eq,
+            "!=" => Op::Ne,
+            "<" => Op::Lt,
+            "<=" => Op::Le,
+            ">" => Op::Gt,
+            ">=" => Op::Ge,
+            _ => {
+                let pattern = Pattern::new(value)
+                    .map_err(|e| format!("Invalid pattern '{}': {}", value, e))?;
+                if op == "~" {
+                    Op::Matches(pattern)
+                } else {
+                    Op::NotMatches(pattern)
+                }
+            }
+        };
+
+        Ok(Self {
+            field,
+            op,
+            value: value.to_string(),
+        })
+    }
+}
+
+/// Resolves a field name or alias to the `VM` field it refers to.
+pub fn parse_field(name: &str) -> Result<&'static str, String> {
+    let name = ALIASES
+        .iter()
+        .find(|(alias, _)| *alias == name)
+        .map_or(name, |(_, field)| field);
+
+    FIELDS
+        .iter()
+        .map(|(field, _)| *field)
+        .find(|field| *field == name)
+        .ok_or_else(|| {
+            let fields: Vec<&str> = FIELDS.iter().map(|(field, _)| *field).collect();
+            format!("Unknown field '{}'. Available: {}", name, fields.join(", "))
+        })
+}
+
+/// Orders two field values: numbers numerically, IP addresses and
+/// timestamps by value, anything else as text.
+fn compare(a: &Value, b: &Value) -> Ordering {
+    if let (Some(a), Some(b)) = (a.as_f64(), b.as_f64()) {
+        return a.total_cmp(&b);
+    }
+
+    let (a, b) = (cell(a), cell(b));
+    if let (Ok(a), Ok(b)) = (a.parse::<IpAddr>(), b.parse::<IpAddr>()) {
+        return a.cmp(&b);
+    }
+    if let (Ok(a), Ok(b)) = (
+        DateTime::<FixedOffset>::parse_from_rfc3339(&a),
+        DateTime::<FixedOffset>::parse_from_rfc3339(&b),
+    ) {
+        return a.cmp(&b);
+    }
+    a.cmp(&b)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn vm() -> Value {
+        json!({
+            "name": "web-1",
+            "cpu": 2,
+            "memory": 2048,
+            "image": "ubuntu-24.04",
+            "ip_address": "172.16.0.10",
+            "created_at": "2026-01-02T03:04:05Z",
+        })
+    }
+
+    fn matches(condition: &str) -> bool {
+        condition.parse::<Condition>().unwrap().matches(&vm())
+    }
+
+    #[test]
+    fn two_character_operators_win() {
+        assert!(matches!("cpu!=2".parse::<Condition>().unwrap().op, Op::Ne));
+        assert!(matches!("cpu<=2".parse::<Condition>().unwrap().op, Op::Le));
+        assert!(matches!("cpu>=2".parse::<Condition>().unwrap().op, Op::Ge));
+        assert!(matches!(
+            "name!~web-*".parse::<Condition>().unwrap().op,
+            Op::NotMatches(_)
+        ));
+        assert_eq!("cpu<=2".parse::<Condition>().unwrap().value, "2");
+    }
+
+    #[test]
+    fn compares_numbers_numerically() {
+        assert!(matches("memory>=1024"));
+        assert!(matches("memory>512"));
+        assert!(!matches("memory<1024"));
+        assert!(matches("cpu=2"));
+        assert!(matches("cpu<=2"));
+        assert!(!matches("cpu!=2"));
+    }
+
+    #[test]
+    fn compares_addresses_and_timestamps_by_value() {
+        assert!(matches("ip<172.16.0.100"));
+        assert!(matches("created>2025-12-31T23:59:59Z"));
+    }
+
+    #[test]
+    fn matches_globs() {
+        assert!(matches("name~web-*"));
+        assert!(!matches("name!~web-*"));
+        assert!(matches("image~ubuntu-*"));
+    }
+
+    #[test]
+    fn resolves_aliases() {
+        assert_eq!(parse_field("ip"), Ok("ip_address"));
+        assert_eq!(parse_field("disk"), Ok("disk_size"));
+        assert!(parse_field("colour").is_err());
+    }
+
+    #[test]
+    fn rejects_globs_on_numeric_fields() {
+        assert!("cpu~2*".parse::<Condition>().is_err());
+        assert!("memory!~1*".parse::<Condition>().is_err());
+    }
+
+    #[test]
+    fn rejects_non_numbers_for_numeric_fields() {
+        assert!("cpu=two".parse::<Condition>().is_err());
+        assert!("memory>=2G".parse::<Condition>().is_err());
+    }
+
+    #[test]
+    fn splits_only_before_conditions() {
+        let conditions: Conditions = "name~web-[a,b]*,cpu>=2, memory<4096".parse().unwrap();
+        assert_eq!(conditions.0.len(), 3);
+        assert_eq!(conditions.0[0].value, "web-[a,b]*");
+        assert!(conditions.0[0].matches(&json!({ "name": "web-b1" })));
+        assert!(!conditions.0[0].matches(&json!({ "name": "web-c1" })));
+        assert_eq!(conditions.0[2].value, "4096");
+    }
+
+    #[test]
+    fn rejects_missing_or_unknown_operators() {
+        assert!("cpu".parse::<Condition>().is_err());
+        assert!("cpu==2".parse::<Condition>().is_err());
+        assert!("cpu^2".parse::<Condition>().is_err());
+    }
+}
@@ -0,0 +1,293 @@
+use anyhow::Result;
+use colored::*;
+use fc_vps_client::{UpdateRequest, VPSClient};
+use std::collections::{BTreeMap, BTreeSet};
+use std::str::FromStr;
+
+use crate::output::{format_labels, print_vm, say, OutputFormat};
+
+/// Longest label key or value, as on the server.
+const MAX_LEN: usize = 63;
+
+/// Up to [`MAX_LEN`] alphanumerics and `extra` characters, starting and
+/// ending with an alphanumeric.
+fn is_valid(s: &str, extra: &[char]) -> bool {
+    s.len() <= MAX_LEN
+        && s.starts_with(|c: char| c.is_ascii_alphanumeric())
+        && s.ends_with(|c: char| c.is_ascii_alphanumeric())
+        && s.chars()
+            .all(|c| c.is_ascii_alphanumeric() || extra.contains(&c))
+}
+
+/// Checks a label key the way the server does. Keys may carry a prefix,
+/// e.g. `example.com/team`.
+fn validate_key(key: &str) -> Result<(), String> {
+    if is_valid(key, &['-', '_', '.', '/']) {
+        Ok(())
+    } else {
+        Err(format!(
+            "Invalid label key '{}': use up to {} letters, digits, '-', '_', '.' and '/', \
+             starting and ending with a letter or digit",
+            key, MAX_LEN
+        ))
+    }
+}
+
+/// Checks a label value the way the server does. Values may be empty.
+fn validate_value(value: &str) -> Result<(), String> {
+    if value.is_empty() || is_valid(value, &['-', '_', '.']) {
+        Ok(())
+    } else {
+        Err(format!(
+            "Invalid label value '{}': use up to {} letters, digits, '-', '_' and '.', \
+             starting and ending with a letter or digit",
+            value, MAX_LEN
+        ))
+    }
+}
+
+/// Parses a `key=value` label, as given to `create --label`.
+pub fn parse_label(s: &str) -> Result<(String, String), String> {
+    let (key, value) = s
+        .split_once('=')
+        .ok_or_else(|| format!("'{}' is not a label; use key=value", s))?;
+    validate_key(key)?;
+    validate_value(value)?;
+    Ok((key.to_string(), value.to_string()))
+}
+
+/// One argument of `fc-vps label`: `key=value` sets a label, `key-`
+/// removes it.
+#[derive(Clone, Debug)]
+pub enum LabelChange {
+    Set(String, String),
+    Remove(String),
+}
+
+impl FromStr for LabelChange {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        if s.contains('=') {
+            let (key, value) = parse_label(s)?;
+            return Ok(LabelChange::Set(key, value));
+        }
+        match s.strip_suffix('-') {
+            Some(key) => {
+                validate_key(key)?;
+                Ok(LabelChange::Remove(key.to_string()))
+            }
+            None => Err(format!(
+                "'{}' is not a label change; use key=value to set it or key- to remove it",
+                s
+            )),
+        }
+    }
+}
+
+/// One `-l/--selector` term: `key=value`, `key!=value`, `key` (has the
+/// label) or `!key` (does not).
+#[derive(Clone, Debug)]
+pub enum Requirement {
+    Eq(String, String),
+    /// Also true for VPS instances without the label.
+    Ne(String, String),
+    Exists(String),
+    NotExists(String),
+}
+
+impl Requirement {
+    pub fn matches(&self, labels: &BTreeMap<String, String>) -> bool {
+        match self {
+            Requirement::Eq(key, value) => labels.get(key) == Some(value),
+            Requirement::Ne(key, value) => labels.get(key) != Some(value),
+            Requirement::Exists(key) => labels.contains_key(key),
+            Requirement::NotExists(key) => !labels.contains_key(key),
+        }
+    }
+}
+
+impl FromStr for Requirement {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let s = s.trim();
+        if let Some((key, value)) = s.split_once("!=") {
+            validate_key(key)?;
+            return Ok(Requirement::Ne(key.to_string(), value.to_string()));
+        }
+        if let Some((key, value)) = s.split_once('=') {
+            validate_key(key)?;
+            return Ok(Requirement::Eq(key.to_string(), value.to_string()));
+        }
+        match s.strip_prefix('!') {
+            Some(key) => {
+                validate_key(key)?;
+                Ok(Requirement::NotExists(key.to_string()))
+            }
+            None => {
+                validate_key(s)?;
+                Ok(Requirement::Exists(s.to_string()))
+            }
+        }
+    }
+}
+
+/// Applies `changes` to the labels of a VPS and sends the result. Removing
+/// a label the VPS doesn't have is not an error.
+pub async fn handle_label(
+    client: &VPSClient,
+    id: &str,
+    changes: &[LabelChange],
+    output: OutputFormat,
+) -> Result<()> {
+    let vm = client.find_vm_by_name_or_id(id).await?;
+
+    let mut labels = vm.labels.clone();
+    for change in changes {
+        match change {
+            LabelChange::Set(key, value) => labels.insert(key.clone(), value.clone()),
+            LabelChange::Remove(key) => labels.remove(key),
+        };
+    }
+
+    if labels == vm.labels {
+        say!(
+            output,
+            "{}",
+            format!("No label changes for VPS '{}'", vm.name).yellow()
+        );
+        if output.is_structured() {
+            return print_vm(&vm, output);
+        }
+        return Ok(());
+    }
+
+    let keys: BTreeSet<&String> = vm.labels.keys().chain(labels.keys()).collect();
+    for key in keys {
+        match (vm.labels.get(key), labels.get(key)) {
+            (None, Some(new)) => say!(output, "  {}", format!("+ {}={}", key, new).green()),
+            (Some(old), None) => say!(output, "  {}", format!("- {}={}", key, old).red()),
+            (Some(old), Some(new)) if old != new => say!(
+                output,
+                "  {}",
+                format!("~ {}: {} → {}", key, old, new).yellow()
+            ),
+            _ => {}
+        }
+    }
+
+    let request = UpdateRequest {
+        labels: Some(labels),
+        ..Default::default()
+    };
+    let updated = client.update_vm(&vm.id, request).await?;
+
+    if output.is_structured() {
+        return print_vm(&updated, output);
+    }
+
+    println!();
+    println!("🏷️  Labels of VPS '{}' updated", updated.name.bold());
+    if updated.labels.is_empty() {
+        println!("   Labels: {}", "<none>".dimmed());
+    } else {
+        println!("   Labels: {}", format_labels(&updated.labels).cyan());
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn labels() -> BTreeMap<String, String> {
+        BTreeMap::from([
+            ("team".to_string(), "payments".to_string()),
+            ("env".to_string(), "staging".to_string()),
+        ])
+    }
+
+    fn matches(selector: &str) -> bool {
+        selector.parse::<Requirement>().unwrap().matches(&labels())
+    }
+
+    #[test]
+    fn parses_requirements() {
+        assert!(matches!(
+            "team=payments".parse(),
+            Ok(Requirement::Eq(key, value)) if key == "team" && value == "payments"
+        ));
+        assert!(matches!(
+            "env!=prod".parse(),
+            Ok(Requirement::Ne(key, value)) if key == "env" && value == "prod"
+        ));
+        assert!(matches!("backup".parse(), Ok(Requirement::Exists(key)) if key == "backup"));
+        assert!(matches!("!temp".parse(), Ok(Requirement::NotExists(key)) if key == "temp"));
+    }
+
+    #[test]
+    fn not_equal_is_not_read_as_equal() {
+        // `env!=prod` must not become `env!` = `prod`
+        assert!(matches!(
+            "env!=prod".parse::<Requirement>(),
+            Ok(Requirement::Ne(..))
+        ));
+    }
+
+    #[test]
+    fn matches_labels() {
+        assert!(matches("team=payments"));
+        assert!(!matches("team=search"));
+        assert!(matches("env!=prod"));
+        assert!(!matches("env!=staging"));
+        // Missing labels are never equal
+        assert!(matches("owner!=alice"));
+        assert!(matches("team"));
+        assert!(!matches("backup"));
+        assert!(matches("!backup"));
+        assert!(!matches("!team"));
+    }
+
+    #[test]
+    fn rejects_invalid_requirement_keys() {
+        assert!("-team=payments".parse::<Requirement>().is_err());
+        assert!("!".parse::<Requirement>().is_err());
+        assert!("".parse::<Requirement>().is_err());
+    }
+
+    #[test]
+    fn parses_label_changes() {
+        assert!(matches!(
+            "team=payments".parse(),
+            Ok(LabelChange::Set(key, value)) if key == "team" && value == "payments"
+        ));
+        assert!(matches!(
+            "team=".parse(),
+            Ok(LabelChange::Set(key, value)) if key == "team" && value.is_empty()
+        ));
+        assert!(matches!("team-".parse(), Ok(LabelChange::Remove(key)) if key == "team"));
+    }
+
+    #[test]
+    fn value_may_end_with_a_dash_only_when_setting() {
+        // `key-` removes, but a value ending in '-' is invalid
+        assert!("team=payments-".parse::<LabelChange>().is_err());
+        assert!(matches!(
+            "cost-center-".parse(),
+            Ok(LabelChange::Remove(key)) if key == "cost-center"
+        ));
+    }
+
+    #[test]
+    fn rejects_invalid_label_changes() {
+        assert!("team".parse::<LabelChange>().is_err());
+        assert!("-".parse::<LabelChange>().is_err());
+        assert!("team--".parse::<LabelChange>().is_err());
+        assert!("te am=x".parse::<LabelChange>().is_err());
+        assert!(format!("{}=x", "k".repeat(MAX_LEN + 1))
+            .parse::<LabelChange>()
+            .is_err());
+    }
+}
@@ -1,28 +1,139 @@
-use anyhow::{Context, Result};
-use chrono::{DateTime, Utc};
-use clap::{Parser, Subcommand};
+use anyhow::Result;
+use clap::{ArgGroup, Parser, Subcommand};
 use colored::*;
 use dialoguer::{Confirm, Input, Select};
-use indicatif::{ProgressBar, ProgressStyle};
-use reqwest::Client;
-use serde::{Deserialize, Serialize};
+use fc_vps_client::{
+    CloneRequest, ResizeRequest, RetryPolicy, TimeoutPhase, TlsOptions, UpdateRequest, VMRequest,
+    VPSClient, VpsError, VM,
+};
+use indicatif::{MultiProgress, ProgressBar};
+use std::path::{Path, PathBuf};
 use std::time::Duration;
 use tabled::{Table, Tabled};
 
+mod auth;
+mod bulk;
+mod config;
+mod edit;
+mod filter;
+mod label;
+mod output;
+mod readiness;
+mod snapshot;
+mod template;
+mod watch;
+
+use auth::AuthScheme;
+use bulk::{Action, BulkArgs};
+use config::{Config, ContextConfig, CreateDefaults, TlsConfig};
+use filter::FilterArgs;
+use label::LabelChange;
+use output::{
+    format_status, print_vm, print_vms, say, short_id, spinner, spinner_in, spinner_with,
+    OutputFormat, SelectArgs,
+};
+use readiness::{Condition, NotReady, Probe, ReadyArgs, Stage};
+use snapshot::SnapshotCommands;
+use template::Template;
+use watch::{Renderer, WatchArgs};
+
+const DEFAULT_SERVER: &str = "http://localhost:8080";
+
 #[derive(Parser)]
 #[command(name = "fc-vps")]
 #[command(version = "0.1.0")]
 #[command(about = "Firecracker VPS Management CLI")]
 #[command(long_about = None)]
 struct Cli {
-    #[arg(short, long, default_value = "http://localhost:8080")]
+    /// API server URL [default: current context's server, or http://localhost:8080]
+    #[arg(short, long)]
     #[arg(env = "FC_VPS_SERVER")]
-    server: String,
+    server: Option<String>,
+
+    /// Context from the config file to use instead of the current one
+    #[arg(long)]
+    #[arg(env = "FC_VPS_CONTEXT")]
+    context: Option<String>,
+
+    /// API token sent with every request
+    #[arg(long)]
+    #[arg(env = "FC_VPS_TOKEN", hide_env_values = true)]
+    token: Option<String>,
+
+    /// Credential helper command whose output is used as the API token
+    #[arg(long)]
+    #[arg(env = "FC_VPS_TOKEN_COMMAND")]
+    token_command: Option<String>,
+
+    /// How the token is sent [default: bearer]
+    #[arg(long, value_enum)]
+    #[arg(env = "FC_VPS_AUTH_SCHEME")]
+    auth_scheme: Option<AuthScheme>,
+
+    /// CA bundle (PEM) used to verify the server certificate
+    #[arg(long)]
+    #[arg(env = "FC_VPS_CA_CERT")]
+    ca_cert: Option<PathBuf>,
+
+    /// Client certificate (PEM) for mutual TLS
+    #[arg(long, requires = "client_key")]
+    #[arg(env = "FC_VPS_CLIENT_CERT")]
+    client_cert: Option<PathBuf>,
+
+    /// Client private key (PKCS#8 PEM) for mutual TLS
+    #[arg(long, requires = "client_cert")]
+    #[arg(env = "FC_VPS_CLIENT_KEY")]
+    client_key: Option<PathBuf>,
+
+    /// Skip server certificate verification (insecure, for testing only)
+    #[arg(long)]
+    #[arg(env = "FC_VPS_INSECURE_SKIP_VERIFY")]
+    insecure_skip_verify: bool,
+
+    /// Output format for command results
+    #[arg(short, long, value_enum, default_value = "table", global = true)]
+    #[arg(env = "FC_VPS_OUTPUT")]
+    output: OutputFormat,
+
+    /// Config file path [default: ~/.config/fc-vps/config.toml]
+    #[arg(long)]
+    #[arg(env = "FC_VPS_CONFIG")]
+    config: Option<PathBuf>,
 
     #[arg(short, long)]
     #[arg(help = "Enable verbose output")]
     verbose: bool,
 
+    /// Maximum attempts per request, including the first one
+    #[arg(long, default_value = "3", value_parser = clap::value_parser!(u32).range(1..))]
+    #[arg(env = "FC_VPS_MAX_ATTEMPTS")]
+    max_attempts: u32,
+
+    /// Delay before the first retry, doubled on every further retry (e.g. 200ms, 1s)
+    #[arg(long, default_value = "200ms", value_parser = humantime::parse_duration)]
+    #[arg(env = "FC_VPS_RETRY_BACKOFF")]
+    retry_backoff: Duration,
+
+    /// Upper bound on the delay between retries
+    #[arg(long, default_value = "5s", value_parser = humantime::parse_duration)]
+    #[arg(env = "FC_VPS_RETRY_MAX_BACKOFF")]
+    retry_max_backoff: Duration,
+
+    /// Time limit for a single API request (e.g. 30s, 2m)
+    #[arg(long, default_value = "30s", value_parser = humantime::parse_duration)]
+    #[arg(env = "FC_VPS_TIMEOUT")]
+    timeout: Duration,
+
+    /// Time limit for connecting to the server
+    #[arg(long, default_value = "10s", value_parser = humantime::parse_duration)]
+    #[arg(env = "FC_VPS_CONNECT_TIMEOUT")]
+    connect_timeout: Duration,
+
+    /// Time limit for slow server-side operations (create, start, stop, delete)
+    #[arg(long, default_value = "10m", value_parser = humantime::parse_duration)]
+    #[arg(env = "FC_VPS_OPERATION_TIMEOUT")]
+    operation_timeout: Duration,
+
     #[command(subcommand)]
     command: Commands,
 }
@@ -35,376 +146,400 @@ enum Commands {
         #[arg(short, long)]
         name: Option<String>,
 
-        /// Number of CPU cores (1-8)
-        #[arg(short, long, default_value = "1")]
-        cpu: u32,
-
-        /// Memory in MB (128-8192)
-        #[arg(short, long, default_value = "512")]
-        memory: u32,
+        /// Number of CPU cores (1-8) [default: 1]
+        #[arg(short, long)]
+        cpu: Option<u32>,
 
-        /// Disk size in GB (1-100)
-        #[arg(short, long, default_value = "10")]
-        disk: u32,
+        /// Memory in MB (128-8192) [default: 512]
+        #[arg(short, long)]
+        memory: Option<u32>,
 
-        /// Base image to use
+        /// Disk size in GB (1-100) [default: 10]
         #[arg(short, long)]
+        disk: Option<u32>,
+
+        /// Base image to use [default: ubuntu-24.04]
+        #[arg(long)]
         image: Option<String>,
 
+        /// Label to attach as key=value; repeat for more
+        #[arg(long = "label", value_name = "KEY=VALUE", value_parser = label::parse_label)]
+        labels: Vec<(String, String)>,
+
         /// Interactive mode
         #[arg(short = 'i', long)]
         interactive: bool,
-    },
-    /// List all VPS instances
-    List {
-        /// Show detailed information
-        #[arg(short, long)]
-        detailed: bool,
 
-        /// Filter by status
-        #[arg(short, long)]
-        status: Option<String>,
+        /// Handlebars template rendered per VPS, or @name for a template file from the config
+        #[arg(long)]
+        template: Option<String>,
     },
-    /// Show VPS details
-    Get {
-        /// VPS ID or name
-        id: String,
+    /// Create a new VPS from a copy of another one's disk
+    Clone {
+        /// Source VPS ID or name
+        source: String,
 
-        /// Show in JSON format
+        /// Name of the new VPS
         #[arg(short, long)]
-        json: bool,
-    },
-    /// Start a VPS
-    Start {
-        /// VPS ID or name
-        id: String,
+        name: String,
 
-        /// Wait for VPS to be ready
+        /// Number of CPU cores (1-8) [default: the source's]
         #[arg(short, long)]
-        wait: bool,
-    },
-    /// Stop a VPS
-    Stop {
-        /// VPS ID or name
-        id: String,
+        cpu: Option<u32>,
 
-        /// Force stop without confirmation
+        /// Memory in MB (128-8192) [default: the source's]
         #[arg(short, long)]
-        force: bool,
+        memory: Option<u32>,
+
+        /// Copy the disk saved in this snapshot of the source instead: ID, prefix or "latest"
+        #[arg(long)]
+        snapshot: Option<String>,
+
+        /// Handlebars template rendered per VPS, or @name for a template file from the config
+        #[arg(long)]
+        template: Option<String>,
     },
-    /// Delete a VPS
-    Delete {
+    /// Change the CPU, memory or disk size of a stopped VPS
+    #[command(group(ArgGroup::new("size").required(true).multiple(true)))]
+    Resize {
         /// VPS ID or name
-        id: String,
+        vm: String,
 
-        /// Force delete without confirmation
-        #[arg(short, long)]
-        force: bool,
-    },
-    /// Show service health
-    Health,
-    /// Interactive management console
-    Console,
-}
+        /// Number of CPU cores (1-8)
+        #[arg(short, long, group = "size")]
+        cpu: Option<u32>,
 
-#[derive(Serialize, Deserialize, Debug)]
-struct VM {
-    id: String,
-    name: String,
-    cpu: u32,
-    memory: u32,
-    disk_size: u32,
-    image: String,
-    status: String,
-    ip_address: String,
-    created_at: DateTime<Utc>,
-    socket_path: String,
-    kernel_path: String,
-    rootfs_path: String,
-    tap_device: String,
-}
+        /// Memory in MB (128-8192)
+        #[arg(short, long, group = "size")]
+        memory: Option<u32>,
 
-#[derive(Tabled)]
-struct VMTableRow {
-    #[tabled(rename = "ID")]
-    id: String,
-    #[tabled(rename = "Name")]
-    name: String,
-    #[tabled(rename = "Status")]
-    status: String,
-    #[tabled(rename = "CPU")]
-    cpu: String,
-    #[tabled(rename = "Memory")]
-    memory: String,
-    #[tabled(rename = "Disk")]
-    disk: String,
-    #[tabled(rename = "IP Address")]
-    ip_address: String,
-    #[tabled(rename = "Created")]
-    created: String,
-}
+        /// Disk size in GB (1-100); the disk can only grow
+        #[arg(short, long, group = "size")]
+        disk: Option<u32>,
 
-#[derive(Serialize)]
-struct VMRequest {
-    name: String,
-    cpu: u32,
-    memory: u32,
-    disk_size: u32,
-    image: String,
-}
+        /// Stop a running VPS first, then start it again and wait until it is ready
+        #[arg(long)]
+        restart: bool,
 
-#[derive(Deserialize)]
-struct ApiResponse<T> {
-    success: bool,
-    message: String,
-    data: Option<T>,
-}
+        /// Restart without confirmation
+        #[arg(short, long, requires = "restart")]
+        force: bool,
 
-struct VPSClient {
-    client: Client,
-    base_url: String,
-    verbose: bool,
-}
+        #[command(flatten)]
+        ready: ReadyArgs,
+    },
+    /// Give a VPS a new name
+    Rename {
+        /// VPS ID or name
+        vm: String,
 
-impl VPSClient {
-    fn new(base_url: String, verbose: bool) -> Self {
-        Self {
-            client: Client::new(),
-            base_url,
-            verbose,
-        }
-    }
+        /// New name of the VPS
+        new_name: String,
+    },
+    /// Edit the name, description and labels of a VPS in $EDITOR
+    Edit {
+        /// VPS ID or name
+        vm: String,
+    },
+    /// Add, change or remove labels of a VPS
+    Label {
+        /// VPS ID or name
+        vm: String,
 
-    async fn create_vm(&self, request: VMRequest) -> Result<VM> {
-        if self.verbose {
-            println!(
-                "Creating VPS with request: {}",
-                serde_json::to_string_pretty(&request)?
-            );
-        }
+        /// key=value to set a label, key- to remove it
+        #[arg(required = true)]
+        changes: Vec<LabelChange>,
+    },
+    /// List all VPS instances
+    List {
+        /// Show detailed information
+        #[arg(short, long, conflicts_with = "watch")]
+        detailed: bool,
 
-        let response = self
-            .client
-            .post(&format!("{}/api/v1/vms", self.base_url))
-            .json(&request)
-            .send()
-            .await
-            .context("Failed to send create VM request")?;
+        #[command(flatten)]
+        filter: FilterArgs,
 
-        let api_response: ApiResponse<VM> = response
-            .json()
-            .await
-            .context("Failed to parse create VM response")?;
+        #[command(flatten)]
+        select: SelectArgs,
 
-        if !api_response.success {
-            anyhow::bail!("API Error: {}", api_response.message);
-        }
+        #[command(flatten)]
+        watch: WatchArgs,
 
-        api_response.data.context("No VM data in response")
-    }
+        /// Handlebars template rendered per VPS, or @name for a template file from the config
+        #[arg(long)]
+        template: Option<String>,
+    },
+    /// Show VPS details
+    Get {
+        /// VPS ID or name
+        id: String,
 
-    async fn list_vms(&self) -> Result<Vec<VM>> {
-        if self.verbose {
-            println!("Fetching VPS list...");
-        }
+        /// Show in JSON format (same as -o json)
+        #[arg(short, long)]
+        json: bool,
 
-        let response = self
-            .client
-            .get(&format!("{}/api/v1/vms", self.base_url))
-            .send()
-            .await
-            .context("Failed to send list VMs request")?;
+        #[command(flatten)]
+        select: SelectArgs,
 
-        let api_response: ApiResponse<Vec<VM>> = response
-            .json()
-            .await
-            .context("Failed to parse list VMs response")?;
+        #[command(flatten)]
+        watch: WatchArgs,
 
-        if !api_response.success {
-            anyhow::bail!("API Error: {}", api_response.message);
-        }
+        /// Handlebars template rendered per VPS, or @name for a template file from the config
+        #[arg(long)]
+        template: Option<String>,
+    },
+    /// Start one or more VPS instances
+    Start {
+        /// VPS IDs, names or name patterns
+        #[arg(required_unless_present_any = ["all", "status", "labels", "filter"])]
+        ids: Vec<String>,
 
-        Ok(api_response.data.unwrap_or_default())
-    }
+        #[command(flatten)]
+        bulk: BulkArgs,
 
-    async fn get_vm(&self, id: &str) -> Result<VM> {
-        if self.verbose {
-            println!("Fetching VPS details for: {}", id);
-        }
+        /// Wait until the VPS is running and passes the readiness probe
+        #[arg(short, long)]
+        wait: bool,
 
-        let response = self
-            .client
-            .get(&format!("{}/api/v1/vms/{}", self.base_url, id))
-            .send()
-            .await
-            .context("Failed to send get VM request")?;
+        #[command(flatten)]
+        ready: ReadyArgs,
+    },
+    /// Stop one or more VPS instances
+    Stop {
+        /// VPS IDs, names or name patterns
+        #[arg(required_unless_present_any = ["all", "status", "labels", "filter"])]
+        ids: Vec<String>,
 
-        let api_response: ApiResponse<VM> = response
-            .json()
-            .await
-            .context("Failed to parse get VM response")?;
+        #[command(flatten)]
+        bulk: BulkArgs,
 
-        if !api_response.success {
-            anyhow::bail!("API Error: {}", api_response.message);
-        }
+        /// Force stop without confirmation
+        #[arg(short, long)]
+        force: bool,
+    },
+    /// Freeze one or more running VPS instances in place
+    Pause {
+        /// VPS IDs, names or name patterns
+        #[arg(required_unless_present_any = ["all", "status", "labels", "filter"])]
+        ids: Vec<String>,
+
+        #[command(flatten)]
+        bulk: BulkArgs,
+    },
+    /// Let one or more paused VPS instances run again
+    Resume {
+        /// VPS IDs, names or name patterns
+        #[arg(required_unless_present_any = ["all", "status", "labels", "filter"])]
+        ids: Vec<String>,
+
+        #[command(flatten)]
+        bulk: BulkArgs,
+    },
+    /// Stop and start one or more VPS instances, then wait until they are ready
+    Restart {
+        /// VPS IDs, names or name patterns
+        #[arg(required_unless_present_any = ["all", "status", "labels", "filter"])]
+        ids: Vec<String>,
 
-        api_response.data.context("No VM data in response")
-    }
+        #[command(flatten)]
+        bulk: BulkArgs,
 
-    async fn start_vm(&self, id: &str) -> Result<()> {
-        if self.verbose {
-            println!("Starting VPS: {}", id);
-        }
+        /// Restart without confirmation
+        #[arg(short, long)]
+        force: bool,
 
-        let response = self
-            .client
-            .post(&format!("{}/api/v1/vms/{}/start", self.base_url, id))
-            .send()
-            .await
-            .context("Failed to send start VM request")?;
+        #[command(flatten)]
+        ready: ReadyArgs,
+    },
+    /// Reboot the guest OS of one or more running VPS instances
+    Reboot {
+        /// VPS IDs, names or name patterns
+        #[arg(required_unless_present_any = ["all", "status", "labels", "filter"])]
+        ids: Vec<String>,
 
-        let api_response: ApiResponse<()> = response
-            .json()
-            .await
-            .context("Failed to parse start VM response")?;
+        #[command(flatten)]
+        bulk: BulkArgs,
 
-        if !api_response.success {
-            anyhow::bail!("API Error: {}", api_response.message);
-        }
+        /// Reboot without confirmation
+        #[arg(short, long)]
+        force: bool,
 
-        Ok(())
-    }
+        /// Wait until the VPS is back and passes the readiness probe
+        #[arg(short, long)]
+        wait: bool,
 
-    async fn stop_vm(&self, id: &str) -> Result<()> {
-        if self.verbose {
-            println!("Stopping VPS: {}", id);
-        }
+        #[command(flatten)]
+        ready: ReadyArgs,
+    },
+    /// Delete one or more VPS instances
+    Delete {
+        /// VPS IDs, names or name patterns
+        #[arg(required_unless_present_any = ["all", "status", "labels", "filter"])]
+        ids: Vec<String>,
 
-        let response = self
-            .client
-            .post(&format!("{}/api/v1/vms/{}/stop", self.base_url, id))
-            .send()
-            .await
-            .context("Failed to send stop VM request")?;
+        #[command(flatten)]
+        bulk: BulkArgs,
 
-        let api_response: ApiResponse<()> = response
-            .json()
-            .await
-            .context("Failed to parse stop VM response")?;
+        /// Force delete without confirmation
+        #[arg(short, long)]
+        force: bool,
+    },
+    /// Block until one or more VPS instances meet a condition
+    Wait {
+        /// VPS IDs or names
+        #[arg(required = true)]
+        ids: Vec<String>,
+
+        /// status=<status>, deleted or ssh-ready
+        #[arg(long = "for", value_name = "CONDITION")]
+        condition: Condition,
+
+        /// How long to wait before giving up
+        #[arg(long, default_value = "2m", value_parser = humantime::parse_duration)]
+        timeout: Duration,
+
+        /// SSH port checked by ssh-ready
+        #[arg(long, default_value_t = 22)]
+        port: u16,
+    },
+    /// Create, list, restore and delete VPS snapshots
+    Snapshot {
+        #[command(subcommand)]
+        action: SnapshotCommands,
+    },
+    /// Show service health
+    Health,
+    /// Interactive management console
+    Console,
+    /// Manage server contexts in the config file
+    Context {
+        #[command(subcommand)]
+        action: ContextCommands,
+    },
+}
 
-        if !api_response.success {
-            anyhow::bail!("API Error: {}", api_response.message);
-        }
+#[derive(Subcommand)]
+enum ContextCommands {
+    /// List all contexts
+    List,
+    /// Switch the current context
+    Use {
+        /// Context name
+        name: String,
+    },
+    /// Add a context, replacing any existing one with the same name
+    Add {
+        /// Context name
+        name: String,
 
-        Ok(())
-    }
+        /// API server URL
+        #[arg(short, long)]
+        server: String,
 
-    async fn delete_vm(&self, id: &str) -> Result<()> {
-        if self.verbose {
-            println!("Deleting VPS: {}", id);
-        }
+        /// API token
+        #[arg(long)]
+        token: Option<String>,
 
-        let response = self
-            .client
-            .delete(&format!("{}/api/v1/vms/{}", self.base_url, id))
-            .send()
-            .await
-            .context("Failed to send delete VM request")?;
+        /// Credential helper command whose output is used as the API token
+        #[arg(long, conflicts_with = "token")]
+        token_command: Option<String>,
 
-        let api_response: ApiResponse<()> = response
-            .json()
-            .await
-            .context("Failed to parse delete VM response")?;
+        /// How the token is sent [default: bearer]
+        #[arg(long, value_enum)]
+        auth_scheme: Option<AuthScheme>,
 
-        if !api_response.success {
-            anyhow::bail!("API Error: {}", api_response.message);
-        }
+        /// CA bundle (PEM) used to verify the server certificate
+        #[arg(long)]
+        ca_cert: Option<PathBuf>,
 
-        Ok(())
-    }
+        /// Client certificate (PEM) for mutual TLS
+        #[arg(long, requires = "client_key")]
+        client_cert: Option<PathBuf>,
 
-    async fn health_check(&self) -> Result<bool> {
-        if self.verbose {
-            println!("Checking service health...");
-        }
+        /// Client private key (PKCS#8 PEM) for mutual TLS
+        #[arg(long, requires = "client_cert")]
+        client_key: Option<PathBuf>,
 
-        let response = self
-            .client
-            .get(&format!("{}/health", self.base_url))
-            .timeout(Duration::from_secs(5))
-            .send()
-            .await
-            .context("Failed to connect to service")?;
+        /// Skip server certificate verification
+        #[arg(long)]
+        insecure_skip_verify: bool,
 
-        Ok(response.status().is_success())
-    }
+        /// Default CPU cores for create
+        #[arg(long)]
+        cpu: Option<u32>,
 
-    async fn find_vm_by_name_or_id(&self, name_or_id: &str) -> Result<VM> {
-        // First try to get by ID
-        if let Ok(vm) = self.get_vm(name_or_id).await {
-            return Ok(vm);
-        }
+        /// Default memory in MB for create
+        #[arg(long)]
+        memory: Option<u32>,
 
-        // If that fails, search by name
-        let vms = self.list_vms().await?;
-        for vm in vms {
-            if vm.name == name_or_id {
-                return Ok(vm);
-            }
-        }
+        /// Default disk size in GB for create
+        #[arg(long)]
+        disk: Option<u32>,
 
-        anyhow::bail!("VPS with name or ID '{}' not found", name_or_id)
-    }
+        /// Default base image for create
+        #[arg(long)]
+        image: Option<String>,
+
+        /// Make this the current context
+        #[arg(long = "use")]
+        make_current: bool,
+    },
+    /// Remove a context
+    Remove {
+        /// Context name
+        name: String,
+    },
 }
 
-impl From<VM> for VMTableRow {
-    fn from(vm: VM) -> Self {
-        Self {
-            id: vm.id[..8].to_string(), // Show short ID
-            name: vm.name,
-            status: match vm.status.as_str() {
-                "running" => vm.status.green().to_string(),
-                "stopped" => vm.status.red().to_string(),
-                "created" => vm.status.yellow().to_string(),
-                _ => vm.status,
-            },
-            cpu: format!("{}c", vm.cpu),
-            memory: format!("{}MB", vm.memory),
-            disk: format!("{}GB", vm.disk_size),
-            ip_address: vm.ip_address,
-            created: vm.created_at.format("%Y-%m-%d %H:%M").to_string(),
-        }
-    }
+#[derive(Tabled)]
+struct ContextTableRow {
+    #[tabled(rename = "Current")]
+    current: String,
+    #[tabled(rename = "Name")]
+    name: String,
+    #[tabled(rename = "Server")]
+    server: String,
 }
 
+/// `defaults` carries the flag/context values; in interactive mode they are
+/// the prompt defaults.
 async fn handle_create(
     client: &VPSClient,
-    name: Option<String>,
-    cpu: u32,
-    memory: u32,
-    disk: u32,
-    image: Option<String>,
+    defaults: VMRequest,
     interactive: bool,
+    output: OutputFormat,
+    template: Option<&Template>,
 ) -> Result<()> {
     let request = if interactive {
-        println!("{}", "🚀 Creating a new VPS".bold().cyan());
-        println!();
+        say!(output, "{}", "🚀 Creating a new VPS".bold().cyan());
+        say!(output);
 
         let name = Input::<String>::new()
             .with_prompt("VPS Name")
-            .default(format!("vps-{}", chrono::Utc::now().timestamp()))
+            .default(defaults.name)
             .interact_text()?;
 
-        let images = vec!["ubuntu-20.04", "ubuntu-22.04", "ubuntu-24.04", "centos-7", "debian-11"];
+        let images = vec![
+            "ubuntu-20.04",
+            "ubuntu-22.04",
+            "ubuntu-24.04",
+            "centos-7",
+            "debian-11",
+        ];
         let image_idx = Select::new()
             .with_prompt("Select base image")
             .items(&images)
-            .default(0)
+            .default(
+                images
+                    .iter()
+                    .position(|i| *i == defaults.image)
+                    .unwrap_or(0),
+            )
             .interact()?;
 
         let cpu = Input::<u32>::new()
             .with_prompt("CPU cores (1-8)")
-            .default(1)
+            .default(defaults.cpu)
             .validate_with(|input: &u32| -> Result<(), &str> {
                 if *input >= 1 && *input <= 8 {
                     Ok(())
@@ -416,7 +551,7 @@ async fn handle_create(
 
         let memory = Input::<u32>::new()
             .with_prompt("Memory in MB (128-8192)")
-            .default(512)
+            .default(defaults.memory)
             .validate_with(|input: &u32| -> Result<(), &str> {
                 if *input >= 128 && *input <= 8192 {
                     Ok(())
@@ -428,7 +563,7 @@ async fn handle_create(
 
         let disk_size = Input::<u32>::new()
             .with_prompt("Disk size in GB (1-100)")
-            .default(10)
+            .default(defaults.disk_size)
             .validate_with(|input: &u32| -> Result<(), &str> {
                 if *input >= 1 && *input <= 100 {
                     Ok(())
@@ -442,47 +577,50 @@ async fn handle_create(
             name,
             cpu,
             memory,
-            disk_size: disk_size,
+            disk_size,
             image: images[image_idx].to_string(),
+            labels: defaults.labels,
         }
     } else {
-        let name = name.unwrap_or_else(|| format!("vps-{}", chrono::Utc::now().timestamp()));
-        let image = image.unwrap_or_else(|| "ubuntu-24.04".to_string());
-
-        // Validate inputs
-        if !(1..=8).contains(&cpu) {
-            anyhow::bail!("CPU cores must be between 1 and 8");
-        }
-        if !(128..=8192).contains(&memory) {
-            anyhow::bail!("Memory must be between 128MB and 8192MB");
-        }
-        if !(1..=100).contains(&disk) {
-            anyhow::bail!("Disk size must be between 1GB and 100GB");
-        }
-
-        VMRequest {
-            name,
-            cpu,
-            memory,
-            disk_size: disk,
-            image,
-        }
+        validate_request(&defaults)?;
+        defaults
     };
 
-    println!("Creating VPS '{}'...", request.name);
+    say!(output, "Creating VPS '{}'...", request.name);
 
-    let pb = ProgressBar::new_spinner();
-    pb.set_style(
-        ProgressStyle::default_spinner()
-            .template("{spinner:.green} {msg}")
-            .unwrap(),
-    );
+    let pb = spinner();
     pb.set_message("Creating VM...");
-    pb.enable_steady_tick(Duration::from_millis(100));
 
     let vm = client.create_vm(request).await?;
     pb.finish_with_message("✅ VPS created successfully!");
 
+    print_created(&vm, output, template)
+}
+
+/// Checks a request against the server's bounds before sending it.
+fn validate_request(request: &VMRequest) -> Result<()> {
+    if !(1..=8).contains(&request.cpu) {
+        anyhow::bail!("CPU cores must be between 1 and 8");
+    }
+    if !(128..=8192).contains(&request.memory) {
+        anyhow::bail!("Memory must be between 128MB and 8192MB");
+    }
+    if !(1..=100).contains(&request.disk_size) {
+        anyhow::bail!("Disk size must be between 1GB and 100GB");
+    }
+    Ok(())
+}
+
+/// Shows a newly created VPS: rendered with the template, in a structured
+/// format, or as a details block with a hint to start it.
+fn print_created(vm: &VM, output: OutputFormat, template: Option<&Template>) -> Result<()> {
+    if let Some(template) = template {
+        return template.print_vm(vm);
+    }
+    if output.is_structured() {
+        return print_vm(vm, output);
+    }
+
     println!();
     println!("{}", "VPS Details:".bold());
     println!("  ID: {}", vm.id);
@@ -501,13 +639,269 @@ async fn handle_create(
     Ok(())
 }
 
+/// `request.snapshot_id` may be a prefix or `latest`; it is resolved to a
+/// full ID before the request is sent.
+async fn handle_clone(
+    client: &VPSClient,
+    source: &str,
+    mut request: CloneRequest,
+    output: OutputFormat,
+    template: Option<&Template>,
+) -> Result<()> {
+    let source = client.find_vm_by_name_or_id(source).await?;
+
+    // Validate the clone as if it were created from scratch
+    validate_request(&VMRequest {
+        name: request.name.clone(),
+        cpu: *request.cpu.get_or_insert(source.cpu),
+        memory: *request.memory.get_or_insert(source.memory),
+        disk_size: source.disk_size,
+        image: source.image.clone(),
+        labels: source.labels.clone(),
+    })?;
+
+    if let Some(snapshot) = &request.snapshot_id {
+        let snapshots = client.list_snapshots(&source.id).await?;
+        request.snapshot_id = Some(snapshot::find_snapshot(snapshots, snapshot)?.id);
+    } else if source.status == "running" {
+        say!(
+            output,
+            "{}",
+            format!(
+                "VPS '{}' will be paused while its disk is copied",
+                source.name
+            )
+            .yellow()
+        );
+    }
+
+    say!(
+        output,
+        "Cloning VPS '{}' as '{}'...",
+        source.name,
+        request.name
+    );
+
+    let pb = spinner();
+    pb.set_message(match &request.snapshot_id {
+        Some(id) => format!("Copying disk from snapshot {}...", short_id(id)),
+        None => "Copying disk...".to_string(),
+    });
+
+    let vm = client.clone_vm(&source.id, request).await?;
+    pb.finish_with_message("✅ VPS cloned successfully!");
+
+    print_created(&vm, output, template)
+}
+
+/// A running or paused VPS must be stopped to resize it. With `restart`
+/// that is done here: stop, resize, start and wait for readiness.
+async fn handle_resize(
+    client: &VPSClient,
+    id: &str,
+    request: ResizeRequest,
+    restart: Option<&ReadyArgs>,
+    force: bool,
+    output: OutputFormat,
+) -> Result<()> {
+    let vm = client.find_vm_by_name_or_id(id).await?;
+
+    // Validate the new sizes as if the VPS were created with them
+    validate_request(&VMRequest {
+        name: vm.name.clone(),
+        cpu: request.cpu.unwrap_or(vm.cpu),
+        memory: request.memory.unwrap_or(vm.memory),
+        disk_size: request.disk_size.unwrap_or(vm.disk_size),
+        image: vm.image.clone(),
+        labels: vm.labels.clone(),
+    })?;
+    if request.disk_size.is_some_and(|disk| disk < vm.disk_size) {
+        anyhow::bail!(
+            "Disk size can only grow; VPS '{}' already has {}GB",
+            vm.name,
+            vm.disk_size
+        );
+    }
+
+    let changes: Vec<String> = [
+        ("CPU", vm.cpu, request.cpu, " cores"),
+        ("Memory", vm.memory, request.memory, "MB"),
+        ("Disk", vm.disk_size, request.disk_size, "GB"),
+    ]
+    .into_iter()
+    .filter_map(|(label, old, new, unit)| {
+        new.filter(|&new| new != old)
+            .map(|new| format!("{}: {}{} → {}{}", label, old, unit, new, unit))
+    })
+    .collect();
+
+    if changes.is_empty() {
+        say!(
+            output,
+            "{}",
+            format!("VPS '{}' already has these resources", vm.name).yellow()
+        );
+        if output.is_structured() {
+            return print_vm(&vm, output);
+        }
+        return Ok(());
+    }
+
+    let up = vm.status == "running" || vm.status == "paused";
+    if up && restart.is_none() {
+        say!(
+            output,
+            "💡 Stop it first with '{}', or pass --restart",
+            format!("fc-vps stop {}", vm.name).cyan()
+        );
+        return Err(VpsError::Conflict(format!(
+            "VPS '{}' is {} and must be stopped to resize",
+            vm.name, vm.status
+        ))
+        .into());
+    }
+
+    say!(output, "Resizing VPS '{}':", vm.name);
+    for change in &changes {
+        say!(output, "  • {}", change);
+    }
+    say!(output);
+
+    if up && !force {
+        let confirm = Confirm::new()
+            .with_prompt(format!("VPS '{}' will be restarted. Continue?", vm.name))
+            .default(false)
+            .interact()?;
+
+        if !confirm {
+            say!(output, "Operation cancelled");
+            return Ok(());
+        }
+    }
+
+    let pb = spinner();
+
+    if up {
+        pb.set_message("Stopping VM...");
+        client.stop_vm(&vm.id).await?;
+    }
+
+    pb.set_message("Resizing VM...");
+    let resized = match client.resize_vm(&vm.id, request).await {
+        Ok(resized) => resized,
+        Err(e) => {
+            // Bring the VPS back with its old resources rather than leave it down
+            if up && client.start_vm(&vm.id).await.is_ok() {
+                pb.finish_with_message("❌ Resize failed, VPS started again unchanged");
+            } else {
+                pb.finish_with_message("❌ Resize failed");
+            }
+            return Err(e.into());
+        }
+    };
+
+    if let Some(ready) = restart.filter(|_| up) {
+        pb.set_message("Starting VM...");
+        client.start_vm(&vm.id).await?;
+        if let Err(e) = readiness::wait_until_ready(client, &vm, ready, &pb).await {
+            pb.finish_with_message("❌ VPS did not become ready");
+            return Err(e);
+        }
+    }
+    pb.finish_with_message("✅ VPS resized successfully!");
+
+    if output.is_structured() {
+        return print_vm(&client.get_vm(&vm.id).await?, output);
+    }
+
+    println!();
+    println!(
+        "📐 VPS '{}' now has {} cores, {}MB memory and a {}GB disk",
+        vm.name.bold(),
+        resized.cpu,
+        resized.memory,
+        resized.disk_size
+    );
+    if !up {
+        println!(
+            "💡 Use '{}' to start it",
+            format!("fc-vps start {}", vm.name).cyan()
+        );
+    }
+
+    Ok(())
+}
+
+async fn handle_rename(
+    client: &VPSClient,
+    id: &str,
+    new_name: &str,
+    output: OutputFormat,
+) -> Result<()> {
+    let vm = client.find_vm_by_name_or_id(id).await?;
+
+    if vm.name == new_name {
+        say!(
+            output,
+            "{}",
+            format!("VPS is already named '{}'", new_name).yellow()
+        );
+        if output.is_structured() {
+            return print_vm(&vm, output);
+        }
+        return Ok(());
+    }
+
+    let request = UpdateRequest {
+        name: Some(new_name.to_string()),
+        ..Default::default()
+    };
+    let renamed = client.update_vm(&vm.id, request).await?;
+
+    if output.is_structured() {
+        return print_vm(&renamed, output);
+    }
+
+    println!("✏️  VPS '{}' renamed to '{}'", vm.name, renamed.name.bold());
+
+    Ok(())
+}
+
 async fn handle_list(
     client: &VPSClient,
     detailed: bool,
-    status_filter: Option<String>,
+    filter: &FilterArgs,
+    select: &SelectArgs,
+    output: OutputFormat,
+    template: Option<&Template>,
+    watch: &WatchArgs,
 ) -> Result<()> {
+    if watch.watch {
+        let renderer = Renderer {
+            interval: watch.interval,
+            select,
+            output,
+            template,
+        };
+        return watch::watch_list(client, filter, renderer).await;
+    }
+
     let vms = client.list_vms().await?;
 
+    let filtered_vms = filter.apply(&vms)?;
+
+    if let Some(template) = template {
+        return template.print_vms(&filtered_vms);
+    }
+    if !select.is_empty() {
+        return select.print(serde_json::to_value(&filtered_vms)?, output);
+    }
+
+    // Structured output is always the (possibly empty) list itself
+    if output.is_structured() {
+        return print_vms(&filtered_vms, output);
+    }
+
     if vms.is_empty() {
         println!("{}", "No VPS instances found".yellow());
         println!(
@@ -517,14 +911,6 @@ async fn handle_list(
         return Ok(());
     }
 
-    let filtered_vms: Vec<VM> = if let Some(status) = status_filter {
-        vms.into_iter()
-            .filter(|vm| vm.status.eq_ignore_ascii_case(&status))
-            .collect()
-    } else {
-        vms
-    };
-
     if filtered_vms.is_empty() {
         println!("{}", "No VPS instances match the filter criteria".yellow());
         return Ok(());
@@ -549,137 +935,238 @@ async fn handle_list(
             println!();
         }
     } else {
-        let table_rows: Vec<VMTableRow> = filtered_vms.into_iter().map(|vm| vm.into()).collect();
-        let table = Table::new(table_rows);
-        println!("{}", table);
+        print_vms(&filtered_vms, output)?;
     }
 
     Ok(())
 }
 
-async fn handle_get(client: &VPSClient, id: &str, json: bool) -> Result<()> {
+async fn handle_get(
+    client: &VPSClient,
+    id: &str,
+    select: &SelectArgs,
+    output: OutputFormat,
+    template: Option<&Template>,
+    watch: &WatchArgs,
+) -> Result<()> {
+    if watch.watch {
+        let renderer = Renderer {
+            interval: watch.interval,
+            select,
+            output,
+            template,
+        };
+        return watch::watch_vm(client, id, renderer).await;
+    }
+
     let vm = client.find_vm_by_name_or_id(id).await?;
 
-    if json {
-        println!("{}", serde_json::to_string_pretty(&vm)?);
-    } else {
-        println!("{}", "VPS Details".bold().cyan());
-        println!("─────────────────────────────────────");
-        println!("{}: {}", "ID".bold(), vm.id);
-        println!("{}: {}", "Name".bold(), vm.name);
-        println!("{}: {}", "Status".bold(), format_status(&vm.status));
-        println!("{}: {} cores", "CPU".bold(), vm.cpu);
-        println!("{}: {}MB", "Memory".bold(), vm.memory);
-        println!("{}: {}GB", "Disk".bold(), vm.disk_size);
-        println!("{}: {}", "Image".bold(), vm.image);
-        println!("{}: {}", "IP Address".bold(), vm.ip_address.cyan());
-        println!("{}: {}", "Socket Path".bold(), vm.socket_path);
-        println!("{}: {}", "Kernel Path".bold(), vm.kernel_path);
-        println!("{}: {}", "Root FS Path".bold(), vm.rootfs_path);
-        println!("{}: {}", "TAP Device".bold(), vm.tap_device);
-        println!(
-            "{}: {}",
-            "Created".bold(),
-            vm.created_at.format("%Y-%m-%d %H:%M:%S UTC")
-        );
+    if let Some(template) = template {
+        return template.print_vm(&vm);
+    }
+    if !select.is_empty() {
+        return select.print(serde_json::to_value(&vm)?, output);
     }
 
-    Ok(())
+    print_vm(&vm, output)
 }
 
-async fn handle_start(client: &VPSClient, id: &str, wait: bool) -> Result<()> {
+async fn handle_start(
+    client: &VPSClient,
+    id: &str,
+    wait: Option<&ReadyArgs>,
+    output: OutputFormat,
+) -> Result<()> {
     let vm = client.find_vm_by_name_or_id(id).await?;
 
-    if vm.status == "running" {
-        println!(
-            "{}",
-            format!("VPS '{}' is already running", vm.name).yellow()
-        );
+    let Some(vm) = bulk::run_one(client, Action::Start(wait), &vm, output).await? else {
         return Ok(());
+    };
+
+    if output.is_structured() {
+        return print_vm(&vm, output);
     }
 
-    println!("Starting VPS '{}'...", vm.name);
+    println!();
+    println!("🎉 VPS '{}' is now running!", vm.name.bold());
+    println!("   IP Address: {}", vm.ip_address.cyan());
+    println!("   SSH: {}", format!("ssh user@{}", vm.ip_address).cyan());
+
+    Ok(())
+}
 
-    let pb = ProgressBar::new_spinner();
-    pb.set_style(
-        ProgressStyle::default_spinner()
-            .template("{spinner:.green} {msg}")
-            .unwrap(),
-    );
-    pb.set_message("Starting VM...");
-    pb.enable_steady_tick(Duration::from_millis(100));
+async fn handle_stop(
+    client: &VPSClient,
+    id: &str,
+    force: bool,
+    output: OutputFormat,
+) -> Result<()> {
+    let vm = client.find_vm_by_name_or_id(id).await?;
 
-    client.start_vm(&vm.id).await?;
+    if !force && Action::Stop.check(&vm)?.is_none() {
+        let confirm = Confirm::new()
+            .with_prompt(format!("Are you sure you want to stop VPS '{}'?", vm.name))
+            .default(false)
+            .interact()?;
 
-    if wait {
-        pb.set_message("Waiting for VM to be ready...");
-        // Add logic to wait for VM to be fully started
-        tokio::time::sleep(Duration::from_secs(3)).await;
+        if !confirm {
+            say!(output, "Operation cancelled");
+            return Ok(());
+        }
     }
 
-    pb.finish_with_message("✅ VPS started successfully!");
+    let Some(vm) = bulk::run_one(client, Action::Stop, &vm, output).await? else {
+        return Ok(());
+    };
+
+    if output.is_structured() {
+        return print_vm(&vm, output);
+    }
 
     println!();
-    println!("🎉 VPS '{}' is now running!", vm.name.bold());
-    println!("   IP Address: {}", vm.ip_address.cyan());
-    println!("   SSH: {}", format!("ssh user@{}", vm.ip_address).cyan());
+    println!("🛑 VPS '{}' has been stopped", vm.name.bold());
 
     Ok(())
 }
 
-async fn handle_stop(client: &VPSClient, id: &str, force: bool) -> Result<()> {
+async fn handle_pause(client: &VPSClient, id: &str, output: OutputFormat) -> Result<()> {
     let vm = client.find_vm_by_name_or_id(id).await?;
 
-    if vm.status == "stopped" {
-        println!(
-            "{}",
-            format!("VPS '{}' is already stopped", vm.name).yellow()
-        );
+    let Some(vm) = bulk::run_one(client, Action::Pause, &vm, output).await? else {
         return Ok(());
+    };
+
+    if output.is_structured() {
+        return print_vm(&vm, output);
     }
 
-    if !force {
+    println!();
+    println!("⏸️  VPS '{}' has been paused", vm.name.bold());
+    println!(
+        "   Resume it with: {}",
+        format!("fc-vps resume {}", vm.name).cyan()
+    );
+
+    Ok(())
+}
+
+async fn handle_resume(client: &VPSClient, id: &str, output: OutputFormat) -> Result<()> {
+    let vm = client.find_vm_by_name_or_id(id).await?;
+
+    let Some(vm) = bulk::run_one(client, Action::Resume, &vm, output).await? else {
+        return Ok(());
+    };
+
+    if output.is_structured() {
+        return print_vm(&vm, output);
+    }
+
+    println!();
+    println!("▶️  VPS '{}' is running again", vm.name.bold());
+
+    Ok(())
+}
+
+async fn handle_restart(
+    client: &VPSClient,
+    id: &str,
+    force: bool,
+    ready: &ReadyArgs,
+    output: OutputFormat,
+) -> Result<()> {
+    let vm = client.find_vm_by_name_or_id(id).await?;
+
+    let up = vm.status == "running" || vm.status == "paused";
+    if !force && up {
         let confirm = Confirm::new()
-            .with_prompt(&format!("Are you sure you want to stop VPS '{}'?", vm.name))
+            .with_prompt(format!(
+                "Are you sure you want to restart VPS '{}'?",
+                vm.name
+            ))
             .default(false)
             .interact()?;
 
         if !confirm {
-            println!("Operation cancelled");
+            say!(output, "Operation cancelled");
             return Ok(());
         }
     }
 
-    println!("Stopping VPS '{}'...", vm.name);
+    let Some(vm) = bulk::run_one(client, Action::Restart(ready), &vm, output).await? else {
+        return Ok(());
+    };
+
+    if output.is_structured() {
+        return print_vm(&vm, output);
+    }
 
-    let pb = ProgressBar::new_spinner();
-    pb.set_style(
-        ProgressStyle::default_spinner()
-            .template("{spinner:.red} {msg}")
-            .unwrap(),
-    );
-    pb.set_message("Stopping VM...");
-    pb.enable_steady_tick(Duration::from_millis(100));
+    println!();
+    println!("🔄 VPS '{}' has been restarted", vm.name.bold());
+    println!("   IP Address: {}", vm.ip_address.cyan());
+    println!("   SSH: {}", format!("ssh user@{}", vm.ip_address).cyan());
+
+    Ok(())
+}
+
+async fn handle_reboot(
+    client: &VPSClient,
+    id: &str,
+    force: bool,
+    wait: Option<&ReadyArgs>,
+    output: OutputFormat,
+) -> Result<()> {
+    let vm = client.find_vm_by_name_or_id(id).await?;
+
+    let action = Action::Reboot(wait);
+    if !force && action.check(&vm)?.is_none() {
+        let confirm = Confirm::new()
+            .with_prompt(format!(
+                "Are you sure you want to reboot VPS '{}'?",
+                vm.name
+            ))
+            .default(false)
+            .interact()?;
+
+        if !confirm {
+            say!(output, "Operation cancelled");
+            return Ok(());
+        }
+    }
+
+    let Some(vm) = bulk::run_one(client, action, &vm, output).await? else {
+        return Ok(());
+    };
 
-    client.stop_vm(&vm.id).await?;
-    pb.finish_with_message("✅ VPS stopped successfully!");
+    if output.is_structured() {
+        return print_vm(&vm, output);
+    }
 
     println!();
-    println!("🛑 VPS '{}' has been stopped", vm.name.bold());
+    println!("🔄 VPS '{}' has been rebooted", vm.name.bold());
 
     Ok(())
 }
 
-async fn handle_delete(client: &VPSClient, id: &str, force: bool) -> Result<()> {
+async fn handle_delete(
+    client: &VPSClient,
+    id: &str,
+    force: bool,
+    output: OutputFormat,
+) -> Result<()> {
     let vm = client.find_vm_by_name_or_id(id).await?;
 
     if !force {
-        println!(
+        say!(
+            output,
             "{}",
             "⚠️  WARNING: This action cannot be undone!".red().bold()
         );
-        println!("VPS '{}' will be permanently deleted.", vm.name.bold());
-        println!();
+        say!(
+            output,
+            "VPS '{}' will be permanently deleted.",
+            vm.name.bold()
+        );
+        say!(output);
 
         let confirm = Confirm::new()
             .with_prompt("Are you absolutely sure you want to delete this VPS?")
@@ -687,24 +1174,19 @@ async fn handle_delete(client: &VPSClient, id: &str, force: bool) -> Result<()>
             .interact()?;
 
         if !confirm {
-            println!("Operation cancelled");
+            say!(output, "Operation cancelled");
             return Ok(());
         }
     }
 
-    println!("Deleting VPS '{}'...", vm.name);
-
-    let pb = ProgressBar::new_spinner();
-    pb.set_style(
-        ProgressStyle::default_spinner()
-            .template("{spinner:.red} {msg}")
-            .unwrap(),
-    );
-    pb.set_message("Deleting VM...");
-    pb.enable_steady_tick(Duration::from_millis(100));
+    let Some(vm) = bulk::run_one(client, Action::Delete, &vm, output).await? else {
+        return Ok(());
+    };
 
-    client.delete_vm(&vm.id).await?;
-    pb.finish_with_message("✅ VPS deleted successfully!");
+    // The VPS as it was just before deletion
+    if output.is_structured() {
+        return print_vm(&vm, output);
+    }
 
     println!();
     println!("🗑️  VPS '{}' has been permanently deleted", vm.name.bold());
@@ -712,17 +1194,104 @@ async fn handle_delete(client: &VPSClient, id: &str, force: bool) -> Result<()>
     Ok(())
 }
 
+async fn handle_wait(
+    client: &VPSClient,
+    ids: &[String],
+    condition: &Condition,
+    timeout: Duration,
+    port: u16,
+    output: OutputFormat,
+) -> Result<()> {
+    let deadline = tokio::time::Instant::now() + timeout;
+    let multi = MultiProgress::new();
+
+    let waits = ids.iter().map(|id| {
+        let pb = spinner_in(&multi);
+        pb.set_message(format!("Waiting for '{}' to be {}...", id, condition));
+
+        async move {
+            let result = wait_for_condition(client, id, condition, deadline, port, &pb).await;
+            pb.finish_and_clear();
+            (id, result)
+        }
+    });
+    let results = futures::future::join_all(waits).await;
+
+    let mut ready = Vec::new();
+    let mut failures = Vec::new();
+    for (id, result) in results {
+        match result {
+            Ok(vm) => {
+                say!(output, "✅ {} {}", id.bold(), condition);
+                ready.extend(vm);
+            }
+            Err(e) => {
+                if ids.len() > 1 {
+                    say!(output, "❌ {}: {:#}", id.bold(), e);
+                }
+                failures.push(e);
+            }
+        }
+    }
+
+    if output.is_structured() {
+        print_vms(&ready, output)?;
+    }
+
+    let failed = failures.len();
+    match failures.into_iter().next() {
+        None => Ok(()),
+        Some(e) if ids.len() == 1 => Err(e),
+        Some(e) => Err(e.context(format!(
+            "{} of {} VPS instances did not become {}",
+            failed,
+            ids.len(),
+            condition
+        ))),
+    }
+}
+
+/// Waits for one VPS. Returns it as last fetched, or `None` once deleted.
+async fn wait_for_condition(
+    client: &VPSClient,
+    id: &str,
+    condition: &Condition,
+    deadline: tokio::time::Instant,
+    port: u16,
+    pb: &ProgressBar,
+) -> Result<Option<VM>> {
+    let vm = match client.find_vm_by_name_or_id(id).await {
+        Ok(vm) => vm,
+        Err(VpsError::NotFound(_)) if *condition == Condition::Deleted => return Ok(None),
+        Err(e) => return Err(e.into()),
+    };
+
+    match condition {
+        Condition::Status(status) => readiness::wait_for_status(client, &vm, status, deadline)
+            .await
+            .map(Some),
+        Condition::Deleted => readiness::wait_for_deletion(client, &vm, deadline)
+            .await
+            .map(|()| None),
+        Condition::SshReady => {
+            let ready = ReadyArgs {
+                probe: Probe::Tcp,
+                port: Some(port),
+                wait_timeout: deadline.saturating_duration_since(tokio::time::Instant::now()),
+                ..ReadyArgs::default()
+            };
+            readiness::wait_until_ready(client, &vm, &ready, pb)
+                .await
+                .map(Some)
+        }
+    }
+}
+
 async fn handle_health(client: &VPSClient) -> Result<()> {
     println!("Checking service health...");
 
-    let pb = ProgressBar::new_spinner();
-    pb.set_style(
-        ProgressStyle::default_spinner()
-            .template("{spinner:.blue} {msg}")
-            .unwrap(),
-    );
+    let pb = spinner_with("{spinner:.blue} {msg}");
     pb.set_message("Connecting...");
-    pb.enable_steady_tick(Duration::from_millis(100));
 
     let healthy = client.health_check().await?;
     pb.finish_and_clear();
@@ -748,6 +1317,8 @@ async fn handle_console(client: &VPSClient) -> Result<()> {
             "Create new VPS",
             "Start VPS",
             "Stop VPS",
+            "Pause VPS",
+            "Resume VPS",
             "Delete VPS",
             "Show VPS details",
             "Check service health",
@@ -762,12 +1333,37 @@ async fn handle_console(client: &VPSClient) -> Result<()> {
 
         match selection {
             0 => {
-                if let Err(e) = handle_list(client, false, None).await {
+                if let Err(e) = handle_list(
+                    client,
+                    false,
+                    &FilterArgs::default(),
+                    &SelectArgs::default(),
+                    OutputFormat::Table,
+                    None,
+                    &WatchArgs::default(),
+                )
+                .await
+                {
                     println!("{}: {}", "Error".red(), e);
                 }
             }
             1 => {
-                if let Err(e) = handle_create(client, None, 1, 512, 10, None, true).await {
+                if let Err(e) = handle_create(
+                    client,
+                    VMRequest {
+                        name: format!("vps-{}", chrono::Utc::now().timestamp()),
+                        cpu: 1,
+                        memory: 512,
+                        disk_size: 10,
+                        image: "ubuntu-24.04".to_string(),
+                        labels: Default::default(),
+                    },
+                    true,
+                    OutputFormat::Table,
+                    None,
+                )
+                .await
+                {
                     println!("{}: {}", "Error".red(), e);
                 }
             }
@@ -780,14 +1376,21 @@ async fn handle_console(client: &VPSClient) -> Result<()> {
 
                 let vm_names: Vec<String> = vms
                     .iter()
-                    .map(|vm| format!("{} ({})", vm.name, vm.id[..8].to_string()))
+                    .map(|vm| format!("{} ({})", vm.name, short_id(&vm.id)))
                     .collect();
                 let vm_idx = Select::new()
                     .with_prompt("Select VPS to start")
                     .items(&vm_names)
                     .interact()?;
 
-                if let Err(e) = handle_start(client, &vms[vm_idx].id, true).await {
+                if let Err(e) = handle_start(
+                    client,
+                    &vms[vm_idx].id,
+                    Some(&ReadyArgs::default()),
+                    OutputFormat::Table,
+                )
+                .await
+                {
                     println!("{}: {}", "Error".red(), e);
                 }
             }
@@ -800,18 +1403,56 @@ async fn handle_console(client: &VPSClient) -> Result<()> {
 
                 let vm_names: Vec<String> = vms
                     .iter()
-                    .map(|vm| format!("{} ({})", vm.name, vm.id[..8].to_string()))
+                    .map(|vm| format!("{} ({})", vm.name, short_id(&vm.id)))
                     .collect();
                 let vm_idx = Select::new()
                     .with_prompt("Select VPS to stop")
                     .items(&vm_names)
                     .interact()?;
 
-                if let Err(e) = handle_stop(client, &vms[vm_idx].id, false).await {
+                if let Err(e) =
+                    handle_stop(client, &vms[vm_idx].id, false, OutputFormat::Table).await
+                {
+                    println!("{}: {}", "Error".red(), e);
+                }
+            }
+            4 | 5 => {
+                let (status, verb) = if selection == 4 {
+                    ("running", "pause")
+                } else {
+                    ("paused", "resume")
+                };
+                let vms: Vec<VM> = client
+                    .list_vms()
+                    .await
+                    .unwrap_or_default()
+                    .into_iter()
+                    .filter(|vm| vm.status == status)
+                    .collect();
+                if vms.is_empty() {
+                    println!("{}", format!("No {} VPS instances found", status).yellow());
+                    continue;
+                }
+
+                let vm_names: Vec<String> = vms
+                    .iter()
+                    .map(|vm| format!("{} ({})", vm.name, short_id(&vm.id)))
+                    .collect();
+                let vm_idx = Select::new()
+                    .with_prompt(format!("Select VPS to {}", verb))
+                    .items(&vm_names)
+                    .interact()?;
+
+                let result = if selection == 4 {
+                    handle_pause(client, &vms[vm_idx].id, OutputFormat::Table).await
+                } else {
+                    handle_resume(client, &vms[vm_idx].id, OutputFormat::Table).await
+                };
+                if let Err(e) = result {
                     println!("{}: {}", "Error".red(), e);
                 }
             }
-            4 => {
+            6 => {
                 let vms = client.list_vms().await.unwrap_or_default();
                 if vms.is_empty() {
                     println!("{}", "No VPS instances found".yellow());
@@ -820,18 +1461,20 @@ async fn handle_console(client: &VPSClient) -> Result<()> {
 
                 let vm_names: Vec<String> = vms
                     .iter()
-                    .map(|vm| format!("{} ({})", vm.name, vm.id[..8].to_string()))
+                    .map(|vm| format!("{} ({})", vm.name, short_id(&vm.id)))
                     .collect();
                 let vm_idx = Select::new()
                     .with_prompt("Select VPS to delete")
                     .items(&vm_names)
                     .interact()?;
 
-                if let Err(e) = handle_delete(client, &vms[vm_idx].id, false).await {
+                if let Err(e) =
+                    handle_delete(client, &vms[vm_idx].id, false, OutputFormat::Table).await
+                {
                     println!("{}: {}", "Error".red(), e);
                 }
             }
-            5 => {
+            7 => {
                 let vms = client.list_vms().await.unwrap_or_default();
                 if vms.is_empty() {
                     println!("{}", "No VPS instances found".yellow());
@@ -840,23 +1483,32 @@ async fn handle_console(client: &VPSClient) -> Result<()> {
 
                 let vm_names: Vec<String> = vms
                     .iter()
-                    .map(|vm| format!("{} ({})", vm.name, vm.id[..8].to_string()))
+                    .map(|vm| format!("{} ({})", vm.name, short_id(&vm.id)))
                     .collect();
                 let vm_idx = Select::new()
                     .with_prompt("Select VPS to view details")
                     .items(&vm_names)
                     .interact()?;
 
-                if let Err(e) = handle_get(client, &vms[vm_idx].id, false).await {
+                if let Err(e) = handle_get(
+                    client,
+                    &vms[vm_idx].id,
+                    &SelectArgs::default(),
+                    OutputFormat::Table,
+                    None,
+                    &WatchArgs::default(),
+                )
+                .await
+                {
                     println!("{}: {}", "Error".red(), e);
                 }
             }
-            6 => {
+            8 => {
                 if let Err(e) = handle_health(client).await {
                     println!("{}: {}", "Error".red(), e);
                 }
             }
-            7 => {
+            9 => {
                 println!("Goodbye! 👋");
                 break;
             }
@@ -871,34 +1523,294 @@ async fn handle_console(client: &VPSClient) -> Result<()> {
     Ok(())
 }
 
-fn format_status(status: &str) -> String {
-    match status {
-        "running" => status.green().to_string(),
-        "stopped" => status.red().to_string(),
-        "created" => status.yellow().to_string(),
-        _ => status.to_string(),
+fn handle_context(config_path: &Path, action: ContextCommands) -> Result<()> {
+    let mut config = Config::load(config_path)?;
+
+    match action {
+        ContextCommands::List => {
+            if config.contexts.is_empty() {
+                println!("{}", "No contexts configured".yellow());
+                println!(
+                    "💡 Add one with: {}",
+                    "fc-vps context add <name> --server <url>".cyan()
+                );
+                return Ok(());
+            }
+
+            let rows: Vec<ContextTableRow> = config
+                .contexts
+                .iter()
+                .map(|(name, context)| ContextTableRow {
+                    current: if config.current_context.as_deref() == Some(name.as_str()) {
+                        "*".green().to_string()
+                    } else {
+                        String::new()
+                    },
+                    name: name.clone(),
+                    server: context.server.clone(),
+                })
+                .collect();
+            println!("{}", Table::new(rows));
+        }
+        ContextCommands::Use { name } => {
+            if !config.contexts.contains_key(&name) {
+                anyhow::bail!("Context '{}' not found", name);
+            }
+            config.current_context = Some(name.clone());
+            config.save(config_path)?;
+            println!("Switched to context '{}'", name.bold());
+        }
+        ContextCommands::Add {
+            name,
+            server,
+            token,
+            token_command,
+            auth_scheme,
+            ca_cert,
+            client_cert,
+            client_key,
+            insecure_skip_verify,
+            cpu,
+            memory,
+            disk,
+            image,
+            make_current,
+        } => {
+            // Stored paths are relative to the config file, so save the
+            // ones given here as absolute paths
+            let absolute = |path: Option<PathBuf>| path.map(std::path::absolute).transpose();
+            let context = ContextConfig {
+                server,
+                token,
+                token_command,
+                auth_scheme,
+                tls: TlsConfig {
+                    ca_cert: absolute(ca_cert)?,
+                    client_cert: absolute(client_cert)?,
+                    client_key: absolute(client_key)?,
+                    insecure_skip_verify,
+                },
+                defaults: CreateDefaults {
+                    cpu,
+                    memory,
+                    disk,
+                    image,
+                },
+                // Only set by editing the file; keep them when re-adding
+                templates: config
+                    .contexts
+                    .get(&name)
+                    .map(|c| c.templates.clone())
+                    .unwrap_or_default(),
+            };
+            let replaced = config.contexts.insert(name.clone(), context).is_some();
+            if make_current || config.current_context.is_none() {
+                config.current_context = Some(name.clone());
+            }
+            config.save(config_path)?;
+
+            if replaced {
+                println!("Updated context '{}'", name.bold());
+            } else {
+                println!("Added context '{}'", name.bold());
+            }
+            if config.current_context.as_deref() == Some(name.as_str()) {
+                println!("Current context is now '{}'", name.bold());
+            }
+        }
+        ContextCommands::Remove { name } => {
+            if config.contexts.remove(&name).is_none() {
+                anyhow::bail!("Context '{}' not found", name);
+            }
+            if config.current_context.as_deref() == Some(name.as_str()) {
+                config.current_context = None;
+            }
+            config.save(config_path)?;
+            println!("Removed context '{}'", name.bold());
+        }
+    }
+
+    Ok(())
+}
+
+/// Process exit codes, so scripts can branch on why a command failed.
+mod exit_code {
+    pub const FAILURE: i32 = 1;
+    pub const TRANSPORT: i32 = 3;
+    pub const HTTP: i32 = 4;
+    pub const DECODE: i32 = 5;
+    pub const NOT_FOUND: i32 = 6;
+    pub const CONFLICT: i32 = 7;
+    pub const VALIDATION: i32 = 8;
+    pub const CAPACITY: i32 = 9;
+    pub const API: i32 = 10;
+    pub const TIMEOUT: i32 = 11;
+    pub const UNAUTHORIZED: i32 = 12;
+    pub const FORBIDDEN: i32 = 13;
+    pub const WAIT_STATUS: i32 = 14;
+    pub const WAIT_PROBE: i32 = 15;
+    pub const AMBIGUOUS: i32 = 16;
+}
+
+fn exit_code_for(err: &anyhow::Error) -> i32 {
+    if let Some(not_ready) = err.downcast_ref::<NotReady>() {
+        return match not_ready.stage {
+            Stage::Status => exit_code::WAIT_STATUS,
+            Stage::Probe(_) => exit_code::WAIT_PROBE,
+        };
+    }
+
+    match err.downcast_ref::<VpsError>() {
+        Some(VpsError::Config(_)) => exit_code::FAILURE,
+        Some(VpsError::Transport(_)) => exit_code::TRANSPORT,
+        Some(VpsError::Timeout { .. }) => exit_code::TIMEOUT,
+        Some(VpsError::Http { .. }) => exit_code::HTTP,
+        Some(VpsError::Unauthorized(_)) => exit_code::UNAUTHORIZED,
+        Some(VpsError::Forbidden(_)) => exit_code::FORBIDDEN,
+        Some(VpsError::Decode { .. }) | Some(VpsError::MissingData) => exit_code::DECODE,
+        Some(VpsError::NotFound(_)) => exit_code::NOT_FOUND,
+        Some(VpsError::Ambiguous(_)) => exit_code::AMBIGUOUS,
+        Some(VpsError::Conflict(_)) => exit_code::CONFLICT,
+        Some(VpsError::Validation(_)) => exit_code::VALIDATION,
+        Some(VpsError::Capacity(_)) => exit_code::CAPACITY,
+        Some(VpsError::Api(_)) => exit_code::API,
+        None => exit_code::FAILURE,
     }
 }
 
 #[tokio::main]
-async fn main() -> Result<()> {
+async fn main() {
     let cli = Cli::parse();
-    let client = VPSClient::new(cli.server.clone(), cli.verbose);
+
+    if let Err(err) = run(cli).await {
+        eprintln!("{}: {:#}", "Error".red(), err);
+        match err.downcast_ref::<VpsError>() {
+            Some(VpsError::Timeout { phase, .. }) => {
+                let flag = match phase {
+                    TimeoutPhase::Connect => "--connect-timeout",
+                    TimeoutPhase::Request => "--timeout",
+                    TimeoutPhase::LongOperation => "--operation-timeout",
+                };
+                eprintln!("💡 Raise the limit with {}", flag.cyan());
+            }
+            Some(VpsError::Unauthorized(_)) => {
+                eprintln!(
+                    "💡 Provide a token with {}, {} or the context's token/token_command",
+                    "--token".cyan(),
+                    "FC_VPS_TOKEN".cyan()
+                );
+            }
+            _ => {}
+        }
+        std::process::exit(exit_code_for(&err));
+    }
+}
+
+async fn run(cli: Cli) -> Result<()> {
+    let config_path = match cli.config.clone() {
+        Some(path) => path,
+        None => config::default_path()?,
+    };
+
+    if let Commands::Context { action } = cli.command {
+        return handle_context(&config_path, action);
+    }
+
+    let config = Config::load(&config_path)?;
+    let context = config.resolve(cli.context.as_deref())?;
+    if let (Some((name, _)), true) = (context, cli.verbose) {
+        println!("Using context '{}'", name);
+    }
+    let context = context.map(|(_, c)| c);
+    let server = cli
+        .server
+        .clone()
+        .or_else(|| context.map(|c| c.server.clone()))
+        .unwrap_or_else(|| DEFAULT_SERVER.to_string());
+    let defaults = context.map(|c| c.defaults.clone()).unwrap_or_default();
+
+    let template = match &cli.command {
+        Commands::Create { template, .. }
+        | Commands::Clone { template, .. }
+        | Commands::List { template, .. }
+        | Commands::Get { template, .. } => template
+            .as_deref()
+            .map(|spec| Template::load(spec, &config_path, &config, context))
+            .transpose()?,
+        _ => None,
+    };
+    let output = match (&template, cli.output) {
+        (Some(_), _) => OutputFormat::Template,
+        (None, OutputFormat::Template) => anyhow::bail!("-o template requires --template"),
+        (None, output) => output,
+    };
+
+    let auth = auth::resolve(
+        cli.token.clone(),
+        cli.token_command.clone(),
+        cli.auth_scheme,
+        context,
+    )?;
+
+    let context_tls = context
+        .map(|c| {
+            c.tls
+                .relative_to(config_path.parent().unwrap_or(Path::new("")))
+        })
+        .unwrap_or_default();
+    let tls = TlsOptions {
+        ca_cert: cli.ca_cert.clone().or(context_tls.ca_cert),
+        client_cert: cli.client_cert.clone().or(context_tls.client_cert),
+        client_key: cli.client_key.clone().or(context_tls.client_key),
+        insecure_skip_verify: cli.insecure_skip_verify || context_tls.insecure_skip_verify,
+    };
+    if tls.insecure_skip_verify {
+        eprintln!(
+            "{}",
+            "⚠️  WARNING: TLS certificate verification is DISABLED. Anyone on the network \
+             path can impersonate the server and read your token."
+                .red()
+                .bold()
+        );
+    }
+
+    let mut builder = VPSClient::builder(server.clone())
+        .verbose(cli.verbose)
+        .retry_policy(RetryPolicy {
+            max_attempts: cli.max_attempts,
+            initial_backoff: cli.retry_backoff,
+            max_backoff: cli.retry_max_backoff,
+            jitter: true,
+        })
+        .timeout(cli.timeout)
+        .connect_timeout(cli.connect_timeout)
+        .long_operation_timeout(cli.operation_timeout)
+        .tls(tls);
+    if let Some(auth) = auth {
+        builder = builder.auth(auth);
+    }
+    let client = builder.build()?;
 
     // Check if service is accessible for most commands
     match &cli.command {
         Commands::Health => {} // Health check will handle its own connectivity
-        _ => {
-            if !client.health_check().await.unwrap_or(false) {
+        _ => match client.health_check().await {
+            Ok(true) => {}
+            result @ (Ok(false) | Err(VpsError::Transport(_))) => {
                 eprintln!(
                     "{}: Cannot connect to Firecracker VPS service at {}",
                     "Error".red(),
-                    cli.server
+                    server
                 );
+                if let Err(err) = result {
+                    // Surfaces TLS failures such as an unknown CA
+                    eprintln!("  {:#}", anyhow::Error::new(err));
+                }
                 eprintln!("Make sure the service is running and the URL is correct.");
-                std::process::exit(1);
+                std::process::exit(exit_code::TRANSPORT);
             }
-        }
+            Err(err) => return Err(err.into()),
+        },
     }
 
     match cli.command {
@@ -908,24 +1820,216 @@ async fn main() -> Result<()> {
             memory,
             disk,
             image,
+            labels,
             interactive,
+            ..
         } => {
-            handle_create(&client, name, cpu, memory, disk, image, interactive).await?;
+            let request = VMRequest {
+                name: name.unwrap_or_else(|| format!("vps-{}", chrono::Utc::now().timestamp())),
+                cpu: cpu.or(defaults.cpu).unwrap_or(1),
+                memory: memory.or(defaults.memory).unwrap_or(512),
+                disk_size: disk.or(defaults.disk).unwrap_or(10),
+                image: image
+                    .or(defaults.image)
+                    .unwrap_or_else(|| "ubuntu-24.04".to_string()),
+                labels: labels.into_iter().collect(),
+            };
+            handle_create(&client, request, interactive, output, template.as_ref()).await?;
         }
-        Commands::List { detailed, status } => {
-            handle_list(&client, detailed, status).await?;
+        Commands::Clone {
+            source,
+            name,
+            cpu,
+            memory,
+            snapshot,
+            ..
+        } => {
+            let request = CloneRequest {
+                name,
+                cpu,
+                memory,
+                snapshot_id: snapshot,
+            };
+            handle_clone(&client, &source, request, output, template.as_ref()).await?;
+        }
+        Commands::Resize {
+            vm,
+            cpu,
+            memory,
+            disk,
+            restart,
+            force,
+            ready,
+        } => {
+            let request = ResizeRequest {
+                cpu,
+                memory,
+                disk_size: disk,
+            };
+            let restart = restart.then_some(&ready);
+            handle_resize(&client, &vm, request, restart, force, output).await?;
+        }
+        Commands::Rename { vm, new_name } => {
+            handle_rename(&client, &vm, &new_name, output).await?;
+        }
+        Commands::Edit { vm } => {
+            edit::handle_edit(&client, &vm, output).await?;
+        }
+        Commands::Label { vm, changes } => {
+            label::handle_label(&client, &vm, &changes, output).await?;
+        }
+        Commands::List {
+            detailed,
+            filter,
+            select,
+            watch,
+            ..
+        } => {
+            handle_list(
+                &client,
+                detailed,
+                &filter,
+                &select,
+                output,
+                template.as_ref(),
+                &watch,
+            )
+            .await?;
+        }
+        Commands::Get {
+            id,
+            json,
+            select,
+            watch,
+            ..
+        } => {
+            let output = if json { OutputFormat::Json } else { output };
+            handle_get(&client, &id, &select, output, template.as_ref(), &watch).await?;
         }
-        Commands::Get { id, json } => {
-            handle_get(&client, &id, json).await?;
+        Commands::Start {
+            ids,
+            bulk,
+            wait,
+            ready,
+        } => {
+            let wait = wait.then_some(&ready);
+            let targets = bulk::select(&client, &ids, &bulk).await?;
+            if bulk.is_single(&ids, &targets) {
+                handle_start(&client, &targets[0].id, wait, output).await?;
+            } else {
+                bulk::run(
+                    &client,
+                    Action::Start(wait),
+                    targets,
+                    bulk.parallel,
+                    true,
+                    output,
+                )
+                .await?;
+            }
+        }
+        Commands::Stop { ids, bulk, force } => {
+            let targets = bulk::select(&client, &ids, &bulk).await?;
+            if bulk.is_single(&ids, &targets) {
+                handle_stop(&client, &targets[0].id, force, output).await?;
+            } else {
+                bulk::run(&client, Action::Stop, targets, bulk.parallel, force, output).await?;
+            }
+        }
+        Commands::Pause { ids, bulk } => {
+            let targets = bulk::select(&client, &ids, &bulk).await?;
+            if bulk.is_single(&ids, &targets) {
+                handle_pause(&client, &targets[0].id, output).await?;
+            } else {
+                bulk::run(&client, Action::Pause, targets, bulk.parallel, true, output).await?;
+            }
+        }
+        Commands::Resume { ids, bulk } => {
+            let targets = bulk::select(&client, &ids, &bulk).await?;
+            if bulk.is_single(&ids, &targets) {
+                handle_resume(&client, &targets[0].id, output).await?;
+            } else {
+                bulk::run(
+                    &client,
+                    Action::Resume,
+                    targets,
+                    bulk.parallel,
+                    true,
+                    output,
+                )
+                .await?;
+            }
         }
-        Commands::Start { id, wait } => {
-            handle_start(&client, &id, wait).await?;
+        Commands::Restart {
+            ids,
+            bulk,
+            force,
+            ready,
+        } => {
+            let targets = bulk::select(&client, &ids, &bulk).await?;
+            if bulk.is_single(&ids, &targets) {
+                handle_restart(&client, &targets[0].id, force, &ready, output).await?;
+            } else {
+                bulk::run(
+                    &client,
+                    Action::Restart(&ready),
+                    targets,
+                    bulk.parallel,
+                    force,
+                    output,
+                )
+                .await?;
+            }
+        }
+        Commands::Reboot {
+            ids,
+            bulk,
+            force,
+            wait,
+            ready,
+        } => {
+            let wait = wait.then_some(&ready);
+            let targets = bulk::select(&client, &ids, &bulk).await?;
+            if bulk.is_single(&ids, &targets) {
+                handle_reboot(&client, &targets[0].id, force, wait, output).await?;
+            } else {
+                bulk::run(
+                    &client,
+                    Action::Reboot(wait),
+                    targets,
+                    bulk.parallel,
+                    force,
+                    output,
+                )
+                .await?;
+            }
+        }
+        Commands::Delete { ids, bulk, force } => {
+            let targets = bulk::select(&client, &ids, &bulk).await?;
+            if bulk.is_single(&ids, &targets) {
+                handle_delete(&client, &targets[0].id, force, output).await?;
+            } else {
+                bulk::run(
+                    &client,
+                    Action::Delete,
+                    targets,
+                    bulk.parallel,
+                    force,
+                    output,
+                )
+                .await?;
+            }
         }
-        Commands::Stop { id, force } => {
-            handle_stop(&client, &id, force).await?;
+        Commands::Wait {
+            ids,
+            condition,
+            timeout,
+            port,
+        } => {
+            handle_wait(&client, &ids, &condition, timeout, port, output).await?;
         }
-        Commands::Delete { id, force } => {
-            handle_delete(&client, &id, force).await?;
+        Commands::Snapshot { action } => {
+            snapshot::handle_snapshot(&client, action, output).await?;
         }
         Commands::Health => {
             handle_health(&client).await?;
@@ -933,6 +2037,7 @@ async fn main() -> Result<()> {
         Commands::Console => {
             handle_console(&client).await?;
         }
+        Commands::Context { .. } => unreachable!("handled before connecting"),
     }
 
     Ok(())
@@ -0,0 +1,459 @@
+use anyhow::{Context, Result};
+use clap::builder::PossibleValuesParser;
+use clap::{Args, ValueEnum};
+use colored::*;
+use fc_vps_client::VM;
+use indicatif::{MultiProgress, ProgressBar, ProgressStyle};
+use serde_json::{Map, Value};
+use serde_json_path::JsonPath;
+use std::collections::{BTreeMap, HashMap};
+use std::time::Duration;
+use tabled::{builder::Builder, Table, Tabled};
+
+/// How command results are printed, selected with `-o/--output`.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, ValueEnum)]
+pub enum OutputFormat {
+    /// Human-readable table (details block for a single VPS)
+    #[default]
+    Table,
+    /// Table with every column
+    Wide,
+    /// JSON: an array for list, an object for single-VPS commands
+    Json,
+    /// YAML, shaped like the JSON output
+    Yaml,
+    /// CSV with a header row
+    Csv,
+    /// VPS names only, one per line
+    Name,
+    /// Render --template for each VPS (implied by --template)
+    Template,
+}
+
+impl OutputFormat {
+    /// Machine-readable formats. Progress and hints go to stderr so stdout
+    /// carries only the data.
+    pub fn is_structured(self) -> bool {
+        !matches!(self, OutputFormat::Table | OutputFormat::Wide)
+    }
+}
+
+/// Prints progress text meant for humans: to stdout for table output, to
+/// stderr for structured formats.
+macro_rules! say {
+    ($output:expr) => {
+        if $output.is_structured() {
+            eprintln!()
+        } else {
+            println!()
+        }
+    };
+    ($output:expr, $($arg:tt)*) => {
+        if $output.is_structured() {
+            eprintln!($($arg)*)
+        } else {
+            println!($($arg)*)
+        }
+    };
+}
+pub(crate) use say;
+
+#[derive(Tabled)]
+pub struct VMTableRow {
+    #[tabled(rename = "ID")]
+    id: String,
+    #[tabled(rename = "Name")]
+    name: String,
+    #[tabled(rename = "Status")]
+    status: String,
+    #[tabled(rename = "CPU")]
+    cpu: String,
+    #[tabled(rename = "Memory")]
+    memory: String,
+    #[tabled(rename = "Disk")]
+    disk: String,
+    #[tabled(rename = "IP Address")]
+    ip_address: String,
+    #[tabled(rename = "Created")]
+    created: String,
+}
+
+impl From<&VM> for VMTableRow {
+    fn from(vm: &VM) -> Self {
+        Self {
+            id: short_id(&vm.id).to_string(),
+            name: vm.name.clone(),
+            status: format_status(&vm.status),
+            cpu: format!("{}c", vm.cpu),
+            memory: format!("{}MB", vm.memory),
+            disk: format!("{}GB", vm.disk_size),
+            ip_address: vm.ip_address.clone(),
+            created: vm.created_at.format("%Y-%m-%d %H:%M").to_string(),
+        }
+    }
+}
+
+#[derive(Tabled)]
+pub struct VMWideTableRow {
+    #[tabled(rename = "ID")]
+    id: String,
+    #[tabled(rename = "Name")]
+    name: String,
+    #[tabled(rename = "Status")]
+    status: String,
+    #[tabled(rename = "CPU")]
+    cpu: String,
+    #[tabled(rename = "Memory")]
+    memory: String,
+    #[tabled(rename = "Disk")]
+    disk: String,
+    #[tabled(rename = "Image")]
+    image: String,
+    #[tabled(rename = "IP Address")]
+    ip_address: String,
+    #[tabled(rename = "TAP Device")]
+    tap_device: String,
+    #[tabled(rename = "Created")]
+    created: String,
+    #[tabled(rename = "Labels")]
+    labels: String,
+}
+
+impl From<&VM> for VMWideTableRow {
+    fn from(vm: &VM) -> Self {
+        Self {
+            id: vm.id.clone(),
+            name: vm.name.clone(),
+            status: format_status(&vm.status),
+            cpu: format!("{}c", vm.cpu),
+            memory: format!("{}MB", vm.memory),
+            disk: format!("{}GB", vm.disk_size),
+            image: vm.image.clone(),
+            ip_address: vm.ip_address.clone(),
+            tap_device: vm.tap_device.clone(),
+            created: vm.created_at.format("%Y-%m-%d %H:%M:%S").to_string(),
+            labels: format_labels(&vm.labels),
+        }
+    }
+}
+
+/// Prints a list of VPS instances in `format`.
+pub fn print_vms(vms: &[VM], format: OutputFormat) -> Result<()> {
+    match format {
+        OutputFormat::Table | OutputFormat::Wide => print_vms_table(vms, format, &HashMap::new())?,
+        OutputFormat::Json => println!("{}", serde_json::to_string_pretty(vms)?),
+        OutputFormat::Yaml => print!("{}", serde_yaml::to_string(vms)?),
+        OutputFormat::Csv => print_csv(vms)?,
+        OutputFormat::Name => {
+            for vm in vms {
+                println!("{}", vm.name);
+            }
+        }
+        OutputFormat::Template => anyhow::bail!("-o template requires --template"),
+    }
+
+    Ok(())
+}
+
+/// Prints a list of VPS instances as a `table` or `wide` table. Those in
+/// `transitions` (ID to previous status) show `old → new` as their status.
+pub fn print_vms_table(
+    vms: &[VM],
+    format: OutputFormat,
+    transitions: &HashMap<String, String>,
+) -> Result<()> {
+    let transition = |vm: &VM| {
+        transitions.get(&vm.id).map(|from| {
+            format!(
+                "{} {} {}",
+                format_status(from),
+                "→".yellow().bold(),
+                format_status(&vm.status)
+            )
+        })
+    };
+
+    if format == OutputFormat::Wide {
+        let rows: Vec<VMWideTableRow> = vms
+            .iter()
+            .map(|vm| VMWideTableRow {
+                status: transition(vm).unwrap_or_else(|| format_status(&vm.status)),
+                ..VMWideTableRow::from(vm)
+            })
+            .collect();
+        println!("{}", Table::new(rows));
+    } else {
+        let rows: Vec<VMTableRow> = vms
+            .iter()
+            .map(|vm| VMTableRow {
+                status: transition(vm).unwrap_or_else(|| format_status(&vm.status)),
+                ..VMTableRow::from(vm)
+            })
+            .collect();
+        println!("{}", Table::new(rows));
+    }
+
+    Ok(())
+}
+
+/// Prints a single VPS in `format`. Table formats show the details block.
+pub fn print_vm(vm: &VM, format: OutputFormat) -> Result<()> {
+    match format {
+        OutputFormat::Table | OutputFormat::Wide => print_vm_details(vm),
+        OutputFormat::Json => println!("{}", serde_json::to_string_pretty(vm)?),
+        OutputFormat::Yaml => print!("{}", serde_yaml::to_string(vm)?),
+        OutputFormat::Csv => print_csv(std::slice::from_ref(vm))?,
+        OutputFormat::Name => println!("{}", vm.name),
+        OutputFormat::Template => anyhow::bail!("-o template requires --template"),
+    }
+
+    Ok(())
+}
+
+pub fn print_vm_details(vm: &VM) {
+    println!("{}", "VPS Details".bold().cyan());
+    println!("─────────────────────────────────────");
+    println!("{}: {}", "ID".bold(), vm.id);
+    println!("{}: {}", "Name".bold(), vm.name);
+    if !vm.description.is_empty() {
+        println!("{}: {}", "Description".bold(), vm.description);
+    }
+    if !vm.labels.is_empty() {
+        println!("{}: {}", "Labels".bold(), format_labels(&vm.labels).cyan());
+    }
+    println!("{}: {}", "Status".bold(), format_status(&vm.status));
+    println!("{}: {} cores", "CPU".bold(), vm.cpu);
+    println!("{}: {}MB", "Memory".bold(), vm.memory);
+    println!("{}: {}GB", "Disk".bold(), vm.disk_size);
+    println!("{}: {}", "Image".bold(), vm.image);
+    println!("{}: {}", "IP Address".bold(), vm.ip_address.cyan());
+    println!("{}: {}", "Socket Path".bold(), vm.socket_path);
+    println!("{}: {}", "Kernel Path".bold(), vm.kernel_path);
+    println!("{}: {}", "Root FS Path".bold(), vm.rootfs_path);
+    println!("{}: {}", "TAP Device".bold(), vm.tap_device);
+    println!(
+        "{}: {}",
+        "Created".bold(),
+        vm.created_at.format("%Y-%m-%d %H:%M:%S UTC")
+    );
+}
+
+/// `VM` fields in serialization order, with their table headers. Also the
+/// CSV header, written explicitly so an empty list still gets one.
+pub const FIELDS: [(&str, &str); 15] = [
+    ("id", "ID"),
+    ("name", "Name"),
+    ("description", "Description"),
+    ("labels", "Labels"),
+    ("cpu", "CPU"),
+    ("memory", "Memory"),
+    ("disk_size", "Disk"),
+    ("image", "Image"),
+    ("status", "Status"),
+    ("ip_address", "IP Address"),
+    ("created_at", "Created"),
+    ("socket_path", "Socket Path"),
+    ("kernel_path", "Kernel Path"),
+    ("rootfs_path", "Root FS Path"),
+    ("tap_device", "TAP Device"),
+];
+
+fn print_csv(vms: &[VM]) -> Result<()> {
+    let mut writer = csv::WriterBuilder::new()
+        .has_headers(false)
+        .from_writer(std::io::stdout());
+    writer.write_record(FIELDS.map(|(field, _)| field))?;
+    for vm in vms {
+        // Written through `cell`, as CSV can't hold the labels map
+        let value = serde_json::to_value(vm)?;
+        writer
+            .write_record(FIELDS.map(|(field, _)| cell(&value[field])))
+            .context("Failed to write CSV")?;
+    }
+    writer.flush()?;
+    Ok(())
+}
+
+/// Labels as `key=value` pairs, comma-separated.
+pub fn format_labels(labels: &BTreeMap<String, String>) -> String {
+    labels
+        .iter()
+        .map(|(key, value)| format!("{}={}", key, value))
+        .collect::<Vec<_>>()
+        .join(",")
+}
+
+pub fn format_status(status: &str) -> String {
+    match status {
+        "running" => status.green().to_string(),
+        "stopped" => status.red().to_string(),
+        "created" => status.yellow().to_string(),
+        "paused" => status.blue().to_string(),
+        "stopping" | "rebooting" | "snapshotting" | "restoring" => status.magenta().to_string(),
+        _ => status.to_string(),
+    }
+}
+
+/// A ticking spinner showing the bar's message.
+pub fn spinner() -> ProgressBar {
+    spinner_with("{spinner:.green} {msg}")
+}
+
+/// A ticking spinner with another template, e.g. another color or with
+/// `{elapsed}`.
+pub fn spinner_with(template: &str) -> ProgressBar {
+    let pb = ProgressBar::new_spinner().with_style(spinner_style(template));
+    pb.enable_steady_tick(Duration::from_millis(100));
+    pb
+}
+
+/// A ticking spinner in `multi`, one per concurrent operation. It only
+/// starts ticking once it is part of `multi`.
+pub fn spinner_in(multi: &MultiProgress) -> ProgressBar {
+    let pb =
+        multi.add(ProgressBar::new_spinner().with_style(spinner_style("{spinner:.green} {msg}")));
+    pb.enable_steady_tick(Duration::from_millis(100));
+    pb
+}
+
+fn spinner_style(template: &str) -> ProgressStyle {
+    ProgressStyle::default_spinner().template(template).unwrap()
+}
+
+/// The 8-character ID prefix shown in tables.
+pub fn short_id(id: &str) -> &str {
+    id.get(..8).unwrap_or(id)
+}
+
+/// Field selection for `list` and `get`, applied to the JSON form of the
+/// `VM`s before printing.
+#[derive(Args, Debug, Default)]
+pub struct SelectArgs {
+    /// Only show these fields, e.g. id,name,ip_address
+    #[arg(long, value_delimiter = ',', conflicts_with_all = ["query", "template"])]
+    #[arg(value_parser = PossibleValuesParser::new(FIELDS.map(|(field, _)| field)))]
+    pub columns: Vec<String>,
+
+    /// JSONPath query over the JSON output, e.g. '$[?@.status=="running"].ip_address'
+    #[arg(long, conflicts_with = "template")]
+    pub query: Option<String>,
+}
+
+impl SelectArgs {
+    pub fn is_empty(&self) -> bool {
+        self.columns.is_empty() && self.query.is_none()
+    }
+
+    /// Prints `value`, one `VM` object or an array of them, narrowed down by
+    /// `--columns` or `--query`.
+    pub fn print(&self, value: Value, format: OutputFormat) -> Result<()> {
+        if let Some(query) = &self.query {
+            return print_query(&value, query, format);
+        }
+
+        let single = value.is_object();
+        let rows: Vec<Map<String, Value>> = match value {
+            Value::Array(items) => items.iter().map(|item| self.project(item)).collect(),
+            item => vec![self.project(&item)],
+        };
+
+        match format {
+            OutputFormat::Table | OutputFormat::Wide => {
+                let mut builder = Builder::default();
+                builder.push_record(self.columns.iter().map(|column| header(column)));
+                for row in &rows {
+                    builder.push_record(row.iter().map(|(field, value)| match field.as_str() {
+                        "status" => format_status(&cell(value)),
+                        _ => cell(value),
+                    }));
+                }
+                println!("{}", builder.build());
+            }
+            OutputFormat::Json | OutputFormat::Yaml => {
+                // A single VPS stays an object, like the unfiltered output
+                let selected = if single {
+                    rows.into_iter()
+                        .next()
+                        .map(Value::Object)
+                        .unwrap_or_default()
+                } else {
+                    Value::Array(rows.into_iter().map(Value::Object).collect())
+                };
+                print_value(&selected, format)?;
+            }
+            OutputFormat::Csv => {
+                let mut writer = csv::Writer::from_writer(std::io::stdout());
+                writer.write_record(&self.columns)?;
+                for row in &rows {
+                    writer.write_record(row.values().map(cell))?;
+                }
+                writer.flush()?;
+            }
+            OutputFormat::Name | OutputFormat::Template => {
+                anyhow::bail!("--columns only works with table, wide, json, yaml and csv output")
+            }
+        }
+
+        Ok(())
+    }
+
+    fn project(&self, item: &Value) -> Map<String, Value> {
+        self.columns
+            .iter()
+            .map(|column| {
+                (
+                    column.clone(),
+                    item.get(column).cloned().unwrap_or(Value::Null),
+                )
+            })
+            .collect()
+    }
+}
+
+/// Runs a JSONPath query. JSON and YAML print the matches as an array; other
+/// formats print one match per line, strings unquoted, for use in scripts.
+fn print_query(value: &Value, query: &str, format: OutputFormat) -> Result<()> {
+    let path = JsonPath::parse(query).with_context(|| format!("Invalid --query '{}'", query))?;
+    let matches = path.query(value).all();
+
+    match format {
+        OutputFormat::Json | OutputFormat::Yaml => print_value(&matches, format)?,
+        _ => {
+            for value in matches {
+                println!("{}", cell(value));
+            }
+        }
+    }
+
+    Ok(())
+}
+
+fn print_value<T: serde::Serialize + ?Sized>(value: &T, format: OutputFormat) -> Result<()> {
+    match format {
+        OutputFormat::Yaml => print!("{}", serde_yaml::to_string(value)?),
+        _ => println!("{}", serde_json::to_string_pretty(value)?),
+    }
+    Ok(())
+}
+
+/// A JSON value as plain text: strings without quotes, `null` as empty,
+/// objects such as labels as `key=value` pairs.
+pub fn cell(value: &Value) -> String {
+    match value {
+        Value::String(s) => s.clone(),
+        Value::Null => String::new(),
+        Value::Object(map) => map
+            .iter()
+            .map(|(key, value)| format!("{}={}", key, cell(value)))
+            .collect::<Vec<_>>()
+            .join(","),
+        other => other.to_string(),
+    }
+}
+
+fn header(field: &str) -> &str {
+    FIELDS
+        .iter()
+        .find(|(name, _)| *name == field)
+        .map_or(field, |(_, header)| header)
+}
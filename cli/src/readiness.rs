@@ -0,0 +1,410 @@
+use anyhow::Result;
+use clap::{Args, ValueEnum};
+use fc_vps_client::{VPSClient, VpsError, VM};
+use indicatif::ProgressBar;
+use std::fmt;
+use std::str::FromStr;
+use std::time::Duration;
+use tokio::io::{AsyncReadExt, AsyncWriteExt};
+use tokio::net::TcpStream;
+use tokio::time::Instant;
+
+/// Pause between two status polls or probe attempts.
+const POLL_INTERVAL: Duration = Duration::from_secs(1);
+
+/// Upper bound for a single probe attempt, so one black-holed SYN does not
+/// eat the whole timeout.
+const ATTEMPT_TIMEOUT: Duration = Duration::from_secs(3);
+
+/// How the guest is checked once the server reports it running.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, ValueEnum)]
+pub enum Probe {
+    /// TCP connect to --port (22 by default)
+    Tcp,
+    /// HTTP GET on --port (80 by default) answered with 2xx or 3xx
+    Http,
+    /// ICMP echo, using the system `ping`
+    Icmp,
+    /// Only wait for the server to report the VPS running
+    None,
+}
+
+#[derive(Args, Debug, Clone)]
+pub struct ReadyArgs {
+    /// Readiness check run after the VPS is running
+    #[arg(long, value_enum, default_value = "tcp")]
+    pub probe: Probe,
+
+    /// Port for the tcp and http probes [default: 22 for tcp, 80 for http]
+    #[arg(long)]
+    pub port: Option<u16>,
+
+    /// Request path for the http probe
+    #[arg(long, default_value = "/")]
+    pub http_path: String,
+
+    /// How long to wait for the VPS to become ready, across all stages
+    #[arg(long, default_value = "2m", value_parser = humantime::parse_duration)]
+    #[arg(env = "FC_VPS_WAIT_TIMEOUT")]
+    pub wait_timeout: Duration,
+}
+
+impl Default for ReadyArgs {
+    fn default() -> Self {
+        Self {
+            probe: Probe::Tcp,
+            port: None,
+            http_path: "/".to_string(),
+            wait_timeout: Duration::from_secs(120),
+        }
+    }
+}
+
+impl ReadyArgs {
+    fn port(&self) -> u16 {
+        self.port.unwrap_or(match self.probe {
+            Probe::Http => 80,
+            _ => 22,
+        })
+    }
+}
+
+/// The statuses the server reports for a VPS.
+const STATUSES: &[&str] = &[
+    "created",
+    "running",
+    "paused",
+    "stopping",
+    "stopped",
+    "rebooting",
+    "snapshotting",
+    "restoring",
+];
+
+/// What `fc-vps wait --for` blocks on.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum Condition {
+    /// `status=<status>`: the server reports this status.
+    Status(String),
+    /// `deleted`: the server no longer knows the VPS.
+    Deleted,
+    /// `ssh-ready`: running, and the guest accepts TCP connections on the
+    /// SSH port.
+    SshReady,
+}
+
+impl FromStr for Condition {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "deleted" => Ok(Condition::Deleted),
+            "ssh-ready" => Ok(Condition::SshReady),
+            _ => match s.strip_prefix("status=") {
+                // A deleted VPS has no status; the server just stops knowing it.
+                Some("deleted") => Ok(Condition::Deleted),
+                Some(status) if STATUSES.contains(&status) => {
+                    Ok(Condition::Status(status.to_string()))
+                }
+                Some(status) => Err(format!(
+                    "'{}' is not a VPS status; use one of {} or deleted",
+                    status,
+                    STATUSES.join(", ")
+                )),
+                None => Err(format!(
+                    "'{}' is not a condition; use status=<status>, deleted or ssh-ready",
+                    s
+                )),
+            },
+        }
+    }
+}
+
+impl fmt::Display for Condition {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Condition::Status(status) => write!(f, "status={}", status),
+            Condition::Deleted => f.write_str("deleted"),
+            Condition::SshReady => f.write_str("ssh-ready"),
+        }
+    }
+}
+
+/// The step of a readiness wait that did not complete.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Stage {
+    /// The server never reported the expected status.
+    Status,
+    /// The VPS was running but the guest did not answer the probe.
+    Probe(Probe),
+}
+
+impl fmt::Display for Stage {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Stage::Status => f.write_str("status"),
+            Stage::Probe(Probe::Tcp) => f.write_str("tcp probe"),
+            Stage::Probe(Probe::Http) => f.write_str("http probe"),
+            Stage::Probe(Probe::Icmp) => f.write_str("icmp probe"),
+            Stage::Probe(Probe::None) => f.write_str("probe"),
+        }
+    }
+}
+
+/// A VPS that did not become ready in time.
+#[derive(Debug)]
+pub struct NotReady {
+    pub vm: String,
+    pub stage: Stage,
+    pub waited: Duration,
+    pub reason: String,
+}
+
+impl fmt::Display for NotReady {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "VPS '{}' not ready after {} (stage: {}): {}",
+            self.vm,
+            humantime::format_duration(Duration::from_secs(
+                self.waited.as_secs_f64().round() as u64
+            )),
+            self.stage,
+            self.reason
+        )
+    }
+}
+
+impl std::error::Error for NotReady {}
+
+/// Waits for the server to report the VPS running and then for the guest to
+/// pass the probe. Returns the VPS as last fetched.
+pub async fn wait_until_ready(
+    client: &VPSClient,
+    vm: &VM,
+    args: &ReadyArgs,
+    pb: &ProgressBar,
+) -> Result<VM> {
+    let started = Instant::now();
+    let deadline = started + args.wait_timeout;
+
+    pb.set_message(format!("Waiting for '{}' to be running...", vm.name));
+    let vm = wait_for_status(client, vm, "running", deadline).await?;
+
+    if args.probe != Probe::None {
+        pb.set_message(format!(
+            "Waiting for {} to answer the {}...",
+            vm.ip_address,
+            Stage::Probe(args.probe)
+        ));
+        probe(&vm, args, deadline).await?;
+    }
+
+    Ok(vm)
+}
+
+/// Polls `get_vm` until the VPS has `status`. Request failures are retried
+/// until the deadline, except for the VPS disappearing.
+pub async fn wait_for_status(
+    client: &VPSClient,
+    vm: &VM,
+    status: &str,
+    deadline: Instant,
+) -> Result<VM> {
+    let started = Instant::now();
+    let mut last = format!("status is '{}'", vm.status);
+
+    loop {
+        match client.get_vm(&vm.id).await {
+            Ok(current) if current.status == status => return Ok(current),
+            Ok(current) => last = format!("status is '{}', not '{}'", current.status, status),
+            Err(e @ VpsError::NotFound(_)) => return Err(e.into()),
+            Err(e) => last = format!("{:#}", anyhow::Error::new(e)),
+        }
+
+        if !pause(deadline).await {
+            return Err(NotReady {
+                vm: vm.name.clone(),
+                stage: Stage::Status,
+                waited: started.elapsed(),
+                reason: last,
+            }
+            .into());
+        }
+    }
+}
+
+/// Polls `get_vm` until the server answers that the VPS does not exist.
+pub async fn wait_for_deletion(client: &VPSClient, vm: &VM, deadline: Instant) -> Result<()> {
+    let started = Instant::now();
+    let mut last = format!("status is '{}'", vm.status);
+
+    loop {
+        match client.get_vm(&vm.id).await {
+            Err(VpsError::NotFound(_)) => return Ok(()),
+            Ok(current) => last = format!("still exists, status is '{}'", current.status),
+            Err(e) => last = format!("{:#}", anyhow::Error::new(e)),
+        }
+
+        if !pause(deadline).await {
+            return Err(NotReady {
+                vm: vm.name.clone(),
+                stage: Stage::Status,
+                waited: started.elapsed(),
+                reason: last,
+            }
+            .into());
+        }
+    }
+}
+
+/// Runs the configured probe against the guest's IP address until it
+/// succeeds or the deadline passes.
+pub async fn probe(vm: &VM, args: &ReadyArgs, deadline: Instant) -> Result<()> {
+    let started = Instant::now();
+    let not_ready = |reason: String| NotReady {
+        vm: vm.name.clone(),
+        stage: Stage::Probe(args.probe),
+        waited: started.elapsed(),
+        reason,
+    };
+
+    if vm.ip_address.is_empty() {
+        return Err(not_ready("the VPS has no IP address".to_string()).into());
+    }
+
+    loop {
+        let remaining = deadline.saturating_duration_since(Instant::now());
+        let attempt = remaining.min(ATTEMPT_TIMEOUT);
+        let result = match args.probe {
+            Probe::Tcp => tcp(&vm.ip_address, args.port(), attempt).await,
+            Probe::Http => http(&vm.ip_address, args.port(), &args.http_path, attempt).await,
+            Probe::Icmp => icmp(&vm.ip_address, attempt).await,
+            Probe::None => Ok(()),
+        };
+
+        match result {
+            Ok(()) => return Ok(()),
+            Err(ProbeError::Fatal(reason)) => return Err(not_ready(reason).into()),
+            Err(ProbeError::Retry(reason)) => {
+                if !pause(deadline).await {
+                    return Err(not_ready(reason).into());
+                }
+            }
+        }
+    }
+}
+
+/// Sleeps for one poll interval, cut short by the deadline. Returns `false`
+/// once the deadline has passed.
+async fn pause(deadline: Instant) -> bool {
+    let now = Instant::now();
+    if now >= deadline {
+        return false;
+    }
+    tokio::time::sleep(POLL_INTERVAL.min(deadline - now)).await;
+    true
+}
+
+enum ProbeError {
+    /// Worth trying again: the guest may still be booting.
+    Retry(String),
+    /// Retrying cannot help, e.g. `ping` is not installed.
+    Fatal(String),
+}
+
+async fn tcp(ip: &str, port: u16, limit: Duration) -> Result<(), ProbeError> {
+    connect(ip, port, limit).await.map(drop)
+}
+
+async fn connect(ip: &str, port: u16, limit: Duration) -> Result<TcpStream, ProbeError> {
+    match tokio::time::timeout(limit, TcpStream::connect((ip, port))).await {
+        Ok(Ok(stream)) => Ok(stream),
+        Ok(Err(e)) => Err(ProbeError::Retry(format!("{}:{}: {}", ip, port, e))),
+        Err(_) => Err(ProbeError::Retry(format!(
+            "{}:{}: no answer within {:?}",
+            ip, port, limit
+        ))),
+    }
+}
+
+async fn http(ip: &str, port: u16, path: &str, limit: Duration) -> Result<(), ProbeError> {
+    let exchange = async {
+        let mut stream = connect(ip, port, limit).await?;
+        let request = format!(
+            "GET {} HTTP/1.0\r\nHost: {}\r\nConnection: close\r\n\r\n",
+            path, ip
+        );
+        let io_error = |e: std::io::Error| ProbeError::Retry(format!("{}:{}: {}", ip, port, e));
+        stream
+            .write_all(request.as_bytes())
+            .await
+            .map_err(io_error)?;
+
+        let mut buf = [0u8; 64];
+        let n = stream.read(&mut buf).await.map_err(io_error)?;
+        let status_line = String::from_utf8_lossy(&buf[..n]);
+        match status_line.split_whitespace().nth(1).map(str::parse::<u16>) {
+            Some(Ok(code)) if (200..400).contains(&code) => Ok(()),
+            Some(Ok(code)) => Err(ProbeError::Retry(format!(
+                "GET http://{}:{}{} returned {}",
+                ip, port, path, code
+            ))),
+            _ => Err(ProbeError::Retry(format!(
+                "{}:{} did not answer with HTTP",
+                ip, port
+            ))),
+        }
+    };
+
+    match tokio::time::timeout(limit, exchange).await {
+        Ok(result) => result,
+        Err(_) => Err(ProbeError::Retry(format!(
+            "GET http://{}:{}{}: no answer within {:?}",
+            ip, port, path, limit
+        ))),
+    }
+}
+
+async fn icmp(ip: &str, limit: Duration) -> Result<(), ProbeError> {
+    let wait = limit.as_secs().max(1).to_string();
+    let status = tokio::process::Command::new("ping")
+        .args(["-c", "1", "-W", &wait, ip])
+        .stdout(std::process::Stdio::null())
+        .stderr(std::process::Stdio::null())
+        .status()
+        .await
+        .map_err(|e| ProbeError::Fatal(format!("cannot run ping: {}", e)))?;
+
+    if status.success() {
+        Ok(())
+    } else {
+        Err(ProbeError::Retry(format!("{} did not answer ping", ip)))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_known_statuses() {
+        for status in STATUSES {
+            let condition: Condition = format!("status={}", status).parse().unwrap();
+            assert_eq!(condition, Condition::Status(status.to_string()));
+        }
+    }
+
+    #[test]
+    fn status_deleted_is_deleted() {
+        assert_eq!("status=deleted".parse(), Ok(Condition::Deleted));
+        assert_eq!("deleted".parse(), Ok(Condition::Deleted));
+    }
+
+    #[test]
+    fn rejects_unknown_status() {
+        assert!("status=runing".parse::<Condition>().is_err());
+        assert!("status=".parse::<Condition>().is_err());
+        assert!("running".parse::<Condition>().is_err());
+    }
+}
@@ -0,0 +1,466 @@
+use anyhow::{Context, Result};
+use clap::builder::{PossibleValuesParser, TypedValueParser};
+use clap::Subcommand;
+use colored::*;
+use dialoguer::Confirm;
+use fc_vps_client::{Snapshot, SnapshotType, VPSClient, VpsError, VM};
+use tabled::{Table, Tabled};
+
+use crate::output::{print_vm, say, short_id, spinner_with, OutputFormat};
+
+/// Shortest snapshot ID prefix accepted, as for VPS IDs.
+const MIN_PREFIX_LEN: usize = 4;
+
+/// Snapshot operations copy whole disks and memory files, so the spinner
+/// also shows how long it has been running.
+const SPINNER: &str = "{spinner:.green} {msg} {elapsed:.dim}";
+
+/// Snapshot fields in serialization order, for the CSV header.
+const FIELDS: [&str; 5] = ["id", "vm_id", "type", "size", "created_at"];
+
+#[derive(Subcommand)]
+pub enum SnapshotCommands {
+    /// Snapshot the memory, VM state and disk of a running or paused VPS
+    Create {
+        /// VPS ID or name
+        vm: String,
+
+        /// full: all guest memory; diff: only memory changed since the previous snapshot
+        #[arg(long = "type", default_value = "full", value_parser = snapshot_type_parser())]
+        snapshot_type: SnapshotType,
+    },
+    /// List the snapshots of a VPS
+    List {
+        /// VPS ID or name
+        vm: String,
+    },
+    /// Roll a VPS back to a snapshot and boot it from there
+    Restore {
+        /// VPS ID or name
+        vm: String,
+
+        /// Snapshot ID, a unique prefix of it, or "latest"
+        snapshot: String,
+
+        /// Restore without confirmation, stopping the VPS if needed
+        #[arg(short, long)]
+        force: bool,
+    },
+    /// Delete a snapshot
+    Delete {
+        /// VPS ID or name
+        vm: String,
+
+        /// Snapshot ID, a unique prefix of it, or "latest"
+        snapshot: String,
+
+        /// Delete without confirmation
+        #[arg(short, long)]
+        force: bool,
+    },
+}
+
+fn snapshot_type_parser() -> impl TypedValueParser<Value = SnapshotType> {
+    PossibleValuesParser::new(["full", "diff"]).map(|s| s.parse::<SnapshotType>().unwrap())
+}
+
+#[derive(Tabled)]
+struct SnapshotTableRow {
+    #[tabled(rename = "ID")]
+    id: String,
+    #[tabled(rename = "Type")]
+    snapshot_type: String,
+    #[tabled(rename = "Size")]
+    size: String,
+    #[tabled(rename = "Created")]
+    created: String,
+}
+
+impl From<&Snapshot> for SnapshotTableRow {
+    fn from(snapshot: &Snapshot) -> Self {
+        Self {
+            id: short_id(&snapshot.id).to_string(),
+            snapshot_type: snapshot.snapshot_type.to_string(),
+            size: format_size(snapshot.size),
+            created: snapshot.created_at.format("%Y-%m-%d %H:%M:%S").to_string(),
+        }
+    }
+}
+
+pub async fn handle_snapshot(
+    client: &VPSClient,
+    action: SnapshotCommands,
+    output: OutputFormat,
+) -> Result<()> {
+    match action {
+        SnapshotCommands::Create { vm, snapshot_type } => {
+            let vm = client.find_vm_by_name_or_id(&vm).await?;
+            create(client, &vm, snapshot_type, output).await
+        }
+        SnapshotCommands::List { vm } => {
+            let vm = client.find_vm_by_name_or_id(&vm).await?;
+            let snapshots = client.list_snapshots(&vm.id).await?;
+            if snapshots.is_empty() && !output.is_structured() {
+                println!("{}", format!("No snapshots of VPS '{}'", vm.name).yellow());
+                return Ok(());
+            }
+            print_snapshots(&snapshots, output)
+        }
+        SnapshotCommands::Restore {
+            vm,
+            snapshot,
+            force,
+        } => {
+            let vm = client.find_vm_by_name_or_id(&vm).await?;
+            let snapshot = find_snapshot(client.list_snapshots(&vm.id).await?, &snapshot)?;
+            restore(client, &vm, &snapshot, force, output).await
+        }
+        SnapshotCommands::Delete {
+            vm,
+            snapshot,
+            force,
+        } => {
+            let vm = client.find_vm_by_name_or_id(&vm).await?;
+            let snapshot = find_snapshot(client.list_snapshots(&vm.id).await?, &snapshot)?;
+            delete(client, &vm, &snapshot, force, output).await
+        }
+    }
+}
+
+async fn create(
+    client: &VPSClient,
+    vm: &VM,
+    snapshot_type: SnapshotType,
+    output: OutputFormat,
+) -> Result<()> {
+    if vm.status == "running" {
+        say!(
+            output,
+            "{}",
+            format!(
+                "VPS '{}' will be paused while the snapshot is taken",
+                vm.name
+            )
+            .yellow()
+        );
+    }
+
+    let pb = spinner_with(SPINNER);
+    pb.set_message(format!(
+        "Creating {} snapshot of '{}'...",
+        snapshot_type, vm.name
+    ));
+
+    let snapshot = match client.create_snapshot(&vm.id, snapshot_type).await {
+        Ok(snapshot) => snapshot,
+        Err(e) => {
+            pb.finish_with_message("❌ Snapshot failed");
+            return Err(e.into());
+        }
+    };
+    pb.finish_with_message("✅ Snapshot created successfully!");
+
+    if output.is_structured() {
+        return print_snapshot(&snapshot, output);
+    }
+
+    println!();
+    println!(
+        "📸 Snapshot {} of VPS '{}'",
+        short_id(&snapshot.id).bold(),
+        vm.name.bold()
+    );
+    println!("   Type: {}", snapshot.snapshot_type);
+    println!("   Size: {}", format_size(snapshot.size));
+    println!(
+        "   Restore it with: {}",
+        format!(
+            "fc-vps snapshot restore {} {}",
+            vm.name,
+            short_id(&snapshot.id)
+        )
+        .cyan()
+    );
+
+    Ok(())
+}
+
+async fn restore(
+    client: &VPSClient,
+    vm: &VM,
+    snapshot: &Snapshot,
+    force: bool,
+    output: OutputFormat,
+) -> Result<()> {
+    let up = vm.status == "running" || vm.status == "paused";
+
+    if !force {
+        say!(
+            output,
+            "{}",
+            "⚠️  WARNING: Changes made since the snapshot will be lost!"
+                .red()
+                .bold()
+        );
+        if up {
+            say!(
+                output,
+                "VPS '{}' is {} and will be stopped first.",
+                vm.name.bold(),
+                vm.status
+            );
+        }
+        say!(output);
+
+        let confirm = Confirm::new()
+            .with_prompt(format!(
+                "Restore VPS '{}' to snapshot {} from {}?",
+                vm.name,
+                short_id(&snapshot.id),
+                snapshot.created_at.format("%Y-%m-%d %H:%M:%S UTC")
+            ))
+            .default(false)
+            .interact()?;
+
+        if !confirm {
+            say!(output, "Operation cancelled");
+            return Ok(());
+        }
+    }
+
+    let pb = spinner_with(SPINNER);
+    if up {
+        pb.set_message(format!("Stopping '{}'...", vm.name));
+        if let Err(e) = client.stop_vm(&vm.id).await {
+            pb.finish_with_message("❌ Restore failed");
+            return Err(e.into());
+        }
+    }
+
+    pb.set_message(format!(
+        "Restoring '{}' to snapshot {}...",
+        vm.name,
+        short_id(&snapshot.id)
+    ));
+    if let Err(e) = client.restore_snapshot(&vm.id, &snapshot.id).await {
+        pb.finish_with_message("❌ Restore failed");
+        return Err(e.into());
+    }
+    pb.finish_with_message("✅ Snapshot restored successfully!");
+
+    if output.is_structured() {
+        return print_vm(&client.get_vm(&vm.id).await?, output);
+    }
+
+    println!();
+    println!(
+        "⏪ VPS '{}' is running from snapshot {}",
+        vm.name.bold(),
+        short_id(&snapshot.id).bold()
+    );
+
+    Ok(())
+}
+
+async fn delete(
+    client: &VPSClient,
+    vm: &VM,
+    snapshot: &Snapshot,
+    force: bool,
+    output: OutputFormat,
+) -> Result<()> {
+    if !force {
+        let confirm = Confirm::new()
+            .with_prompt(format!(
+                "Are you sure you want to delete snapshot {} of VPS '{}'?",
+                short_id(&snapshot.id),
+                vm.name
+            ))
+            .default(false)
+            .interact()?;
+
+        if !confirm {
+            say!(output, "Operation cancelled");
+            return Ok(());
+        }
+    }
+
+    let pb = spinner_with(SPINNER);
+    pb.set_message("Deleting snapshot...");
+    client.delete_snapshot(&vm.id, &snapshot.id).await?;
+    pb.finish_with_message("✅ Snapshot deleted successfully!");
+
+    // The snapshot as it was just before deletion
+    if output.is_structured() {
+        return print_snapshot(snapshot, output);
+    }
+
+    println!();
+    println!(
+        "🗑️  Snapshot {} of VPS '{}' has been deleted",
+        short_id(&snapshot.id).bold(),
+        vm.name.bold()
+    );
+
+    Ok(())
+}
+
+/// Picks the snapshot `query` refers to: a full ID, an ID prefix of at
+/// least [`MIN_PREFIX_LEN`] characters, or `latest`.
+pub fn find_snapshot(snapshots: Vec<Snapshot>, query: &str) -> Result<Snapshot> {
+    if query == "latest" {
+        return snapshots
+            .into_iter()
+            .max_by_key(|snapshot| snapshot.created_at)
+            .context("The VPS has no snapshots");
+    }
+
+    // IDs are lowercase UUIDs; match them the way VPS IDs are matched
+    let id = query.to_ascii_lowercase();
+    let found: Vec<Snapshot> = snapshots
+        .into_iter()
+        .filter(|snapshot| {
+            snapshot.id == id || (id.len() >= MIN_PREFIX_LEN && snapshot.id.starts_with(&id))
+        })
+        .collect();
+
+    match found.len() {
+        1 => Ok(found.into_iter().next().unwrap()),
+        0 => Err(VpsError::NotFound(format!("Snapshot '{}' not found", query)).into()),
+        n => {
+            let candidates: Vec<&str> = found.iter().map(|s| short_id(&s.id)).collect();
+            Err(VpsError::Ambiguous(format!(
+                "'{}' matches {} snapshots, candidates: {}",
+                query,
+                n,
+                candidates.join(", ")
+            ))
+            .into())
+        }
+    }
+}
+
+fn print_snapshots(snapshots: &[Snapshot], format: OutputFormat) -> Result<()> {
+    match format {
+        OutputFormat::Table | OutputFormat::Wide => {
+            let rows: Vec<SnapshotTableRow> = snapshots.iter().map(Into::into).collect();
+            println!("{}", Table::new(rows));
+        }
+        OutputFormat::Json => println!("{}", serde_json::to_string_pretty(snapshots)?),
+        OutputFormat::Yaml => print!("{}", serde_yaml::to_string(snapshots)?),
+        OutputFormat::Csv => {
+            let mut writer = csv::WriterBuilder::new()
+                .has_headers(false)
+                .from_writer(std::io::stdout());
+            writer.write_record(FIELDS)?;
+            for snapshot in snapshots {
+                writer.serialize(snapshot).context("Failed to write CSV")?;
+            }
+            writer.flush()?;
+        }
+        OutputFormat::Name => {
+            for snapshot in snapshots {
+                println!("{}", snapshot.id);
+            }
+        }
+        OutputFormat::Template => anyhow::bail!("--template is not supported for snapshots"),
+    }
+
+    Ok(())
+}
+
+fn print_snapshot(snapshot: &Snapshot, format: OutputFormat) -> Result<()> {
+    match format {
+        OutputFormat::Json => println!("{}", serde_json::to_string_pretty(snapshot)?),
+        OutputFormat::Yaml => print!("{}", serde_yaml::to_string(snapshot)?),
+        _ => print_snapshots(std::slice::from_ref(snapshot), format)?,
+    }
+
+    Ok(())
+}
+
+/// Human-readable size, e.g. `1.5 GB`.
+fn format_size(bytes: u64) -> String {
+    const UNITS: [&str; 4] = ["B", "KB", "MB", "GB"];
+
+    let mut size = bytes as f64;
+    let mut unit = 0;
+    while size >= 1024.0 && unit < UNITS.len() - 1 {
+        size /= 1024.0;
+        unit += 1;
+    }
+
+    if unit == 0 {
+        format!("{} B", bytes)
+    } else {
+        format!("{:.1} {}", size, UNITS[unit])
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::{TimeZone, Utc};
+    use fc_vps_client::SnapshotType;
+
+    fn snapshot(id: &str, hour: u32) -> Snapshot {
+        Snapshot {
+            id: id.to_string(),
+            vm_id: "3f2a9c10-0000-4000-8000-000000000001".to_string(),
+            snapshot_type: SnapshotType::Full,
+            size: 0,
+            created_at: Utc.with_ymd_and_hms(2026, 1, 1, hour, 0, 0).unwrap(),
+        }
+    }
+
+    fn snapshots() -> Vec<Snapshot> {
+        vec![
+            snapshot("0a5952a3-0000-4000-8000-000000000001", 1),
+            snapshot("0a59b7c1-0000-4000-8000-000000000002", 3),
+            snapshot("7e11d0f2-0000-4000-8000-000000000003", 2),
+        ]
+    }
+
+    fn find(query: &str) -> Result<String> {
+        find_snapshot(snapshots(), query).map(|snapshot| snapshot.id)
+    }
+
+    #[test]
+    fn matches_full_id_and_prefix() {
+        assert_eq!(
+            find("7e11d0f2-0000-4000-8000-000000000003").unwrap(),
+            "7e11d0f2-0000-4000-8000-000000000003"
+        );
+        assert_eq!(
+            find("0a5952").unwrap(),
+            "0a5952a3-0000-4000-8000-000000000001"
+        );
+    }
+
+    #[test]
+    fn matches_ids_case_insensitively() {
+        assert_eq!(
+            find("0A5952").unwrap(),
+            "0a5952a3-0000-4000-8000-000000000001"
+        );
+        assert_eq!(
+            find("7E11D0F2-0000-4000-8000-000000000003").unwrap(),
+            "7e11d0f2-0000-4000-8000-000000000003"
+        );
+    }
+
+    #[test]
+    fn latest_is_the_newest() {
+        assert_eq!(
+            find("latest").unwrap(),
+            "0a59b7c1-0000-4000-8000-000000000002"
+        );
+    }
+
+    #[test]
+    fn short_or_shared_prefixes_fail() {
+        assert!(find("0a5").is_err());
+        assert!(find("0a59").is_err());
+        assert!(find("ffff").is_err());
+    }
+}
@@ -0,0 +1,93 @@
+use anyhow::{Context, Result};
+use fc_vps_client::VM;
+use handlebars::Handlebars;
+use std::fs;
+use std::path::Path;
+
+use crate::config::{Config, ContextConfig};
+
+const NAME: &str = "output";
+
+/// A Handlebars template rendered once per VPS, e.g.
+/// `{{name}} ansible_host={{ip_address}}`.
+pub struct Template {
+    registry: Handlebars<'static>,
+}
+
+impl Template {
+    /// `spec` is either the template itself or `@name`, a template file
+    /// listed under `[templates]` in the current context or at the top of
+    /// the config file. Relative paths are resolved against the config
+    /// file's directory.
+    pub fn load(
+        spec: &str,
+        config_path: &Path,
+        config: &Config,
+        context: Option<&ContextConfig>,
+    ) -> Result<Self> {
+        let source = match spec.strip_prefix('@') {
+            Some(name) => {
+                let path = context
+                    .and_then(|c| c.templates.get(name))
+                    .or_else(|| config.templates.get(name))
+                    .with_context(|| {
+                        let mut names: Vec<&str> = config
+                            .templates
+                            .keys()
+                            .chain(context.into_iter().flat_map(|c| c.templates.keys()))
+                            .map(String::as_str)
+                            .collect();
+                        names.sort_unstable();
+                        names.dedup();
+                        format!(
+                            "Template '{}' not found in {}. Available: {}",
+                            name,
+                            config_path.display(),
+                            if names.is_empty() {
+                                "none".to_string()
+                            } else {
+                                names.join(", ")
+                            }
+                        )
+                    })?;
+                let path = config_path
+                    .parent()
+                    .map_or_else(|| path.clone(), |dir| dir.join(path));
+                fs::read_to_string(&path)
+                    .with_context(|| format!("Failed to read template {}", path.display()))?
+            }
+            None => spec.to_string(),
+        };
+
+        let mut registry = Handlebars::new();
+        // Output is plain text, and a misspelled field should fail loudly
+        // instead of rendering as an empty string
+        registry.register_escape_fn(handlebars::no_escape);
+        registry.set_strict_mode(true);
+        registry
+            .register_template_string(NAME, source)
+            .context("Invalid template")?;
+
+        Ok(Self { registry })
+    }
+
+    pub fn print_vm(&self, vm: &VM) -> Result<()> {
+        let rendered = self
+            .registry
+            .render(NAME, vm)
+            .with_context(|| format!("Failed to render template for VPS '{}'", vm.name))?;
+
+        // Template files usually end with a newline; inline ones don't
+        if rendered.ends_with('\n') {
+            print!("{}", rendered);
+        } else {
+            println!("{}", rendered);
+        }
+
+        Ok(())
+    }
+
+    pub fn print_vms(&self, vms: &[VM]) -> Result<()> {
+        vms.iter().try_for_each(|vm| self.print_vm(vm))
+    }
+}
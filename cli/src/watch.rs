@@ -0,0 +1,245 @@
+use anyhow::Result;
+use chrono::Local;
+use clap::Args;
+use colored::*;
+use fc_vps_client::{VPSClient, VpsError, VM};
+use std::collections::HashMap;
+use std::io::IsTerminal;
+use std::time::Duration;
+
+use crate::filter::FilterArgs;
+use crate::output::{
+    format_status, print_vm_details, print_vms, print_vms_table, say, OutputFormat, SelectArgs,
+};
+use crate::template::Template;
+
+#[derive(Args, Debug, Default)]
+pub struct WatchArgs {
+    /// Keep polling and redraw when a VPS changes
+    #[arg(short, long)]
+    pub watch: bool,
+
+    /// How often --watch polls the server
+    #[arg(long, default_value = "2s", value_parser = humantime::parse_duration, requires = "watch")]
+    pub interval: Duration,
+}
+
+/// What happened to one VPS between two polls.
+enum Change {
+    Added(VM),
+    /// Any field changed; `from` is the previous status.
+    Updated {
+        vm: VM,
+        from: String,
+    },
+    Removed(VM),
+}
+
+/// How each poll with changes is shown.
+///
+/// Table formats redraw the whole table with status transitions
+/// highlighted. Every other format, `--columns`, `--query` and `--template`
+/// print only the VPS instances that changed, so the output can be
+/// consumed as a stream.
+pub struct Renderer<'a> {
+    pub interval: Duration,
+    pub select: &'a SelectArgs,
+    pub output: OutputFormat,
+    pub template: Option<&'a Template>,
+}
+
+/// Polls the VPS list until interrupted, printing it whenever it changes.
+pub async fn watch_list(
+    client: &VPSClient,
+    filter: &FilterArgs,
+    renderer: Renderer<'_>,
+) -> Result<()> {
+    let mut previous: Option<Vec<VM>> = None;
+
+    loop {
+        match client.list_vms().await {
+            Ok(vms) => {
+                let vms = filter.apply(&vms)?;
+                let changes = diff(previous.as_deref().unwrap_or_default(), &vms);
+                if previous.is_none() || !changes.is_empty() {
+                    renderer.render(&vms, &changes, previous.is_none(), false)?;
+                }
+                previous = Some(vms);
+            }
+            Err(e) => warn(&renderer, e),
+        }
+
+        tokio::time::sleep(renderer.interval).await;
+    }
+}
+
+/// Polls one VPS until it is deleted or the command is interrupted.
+pub async fn watch_vm(client: &VPSClient, id: &str, renderer: Renderer<'_>) -> Result<()> {
+    // Resolve a name once so a rename does not lose track of the VPS
+    let vm = client.find_vm_by_name_or_id(id).await?;
+    renderer.render(std::slice::from_ref(&vm), &[], true, true)?;
+    let mut previous = vec![vm];
+
+    loop {
+        tokio::time::sleep(renderer.interval).await;
+
+        match client.get_vm(&previous[0].id).await {
+            Ok(vm) => {
+                let current = vec![vm];
+                let changes = diff(&previous, &current);
+                if !changes.is_empty() {
+                    renderer.render(&current, &changes, false, true)?;
+                }
+                previous = current;
+            }
+            Err(VpsError::NotFound(_)) => {
+                let changes = diff(&previous, &[]);
+                renderer.render(&[], &changes, false, true)?;
+                return Ok(());
+            }
+            Err(e) => warn(&renderer, e),
+        }
+    }
+}
+
+impl Renderer<'_> {
+    fn render(&self, vms: &[VM], changes: &[Change], first: bool, single: bool) -> Result<()> {
+        let table =
+            self.template.is_none() && self.select.is_empty() && !self.output.is_structured();
+        if !table {
+            return self.stream(vms, changes, first);
+        }
+
+        if std::io::stdout().is_terminal() {
+            // Clear the screen and move the cursor home, like watch(1)
+            print!("\x1b[2J\x1b[H");
+        } else if !first {
+            println!();
+        }
+        println!(
+            "{}",
+            format!(
+                "Every {}: fc-vps {}    {}",
+                humantime::format_duration(self.interval),
+                std::env::args().skip(1).collect::<Vec<_>>().join(" "),
+                Local::now().format("%H:%M:%S")
+            )
+            .dimmed()
+        );
+        println!();
+
+        let transitions: HashMap<String, String> = changes
+            .iter()
+            .filter_map(|change| match change {
+                Change::Updated { vm, from } if *from != vm.status => {
+                    Some((vm.id.clone(), from.clone()))
+                }
+                _ => None,
+            })
+            .collect();
+
+        match vms {
+            [] if single => {}
+            [] => println!("{}", "No VPS instances found".yellow()),
+            [vm] if single => print_vm_details(vm),
+            vms => print_vms_table(vms, self.output, &transitions)?,
+        }
+
+        if !first && !changes.is_empty() {
+            println!();
+            for change in changes {
+                println!("{}", describe(change));
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Prints only what changed, in the requested format. Deletions are
+    /// reported on stderr for machine-readable output.
+    fn stream(&self, vms: &[VM], changes: &[Change], first: bool) -> Result<()> {
+        let changed: Vec<VM> = if first {
+            vms.to_vec()
+        } else {
+            changes
+                .iter()
+                .filter_map(|change| match change {
+                    Change::Added(vm) | Change::Updated { vm, .. } => Some(vm.clone()),
+                    Change::Removed(_) => None,
+                })
+                .collect()
+        };
+
+        if !changed.is_empty() {
+            if let Some(template) = self.template {
+                template.print_vms(&changed)?;
+            } else if !self.select.is_empty() {
+                self.select
+                    .print(serde_json::to_value(&changed)?, self.output)?;
+            } else {
+                print_vms(&changed, self.output)?;
+            }
+        }
+
+        for change in changes {
+            if let Change::Removed(_) = change {
+                say!(self.output, "{}", describe(change));
+            }
+        }
+
+        Ok(())
+    }
+}
+
+fn diff(previous: &[VM], current: &[VM]) -> Vec<Change> {
+    let before: HashMap<&str, &VM> = previous.iter().map(|vm| (vm.id.as_str(), vm)).collect();
+    let after: HashMap<&str, &VM> = current.iter().map(|vm| (vm.id.as_str(), vm)).collect();
+
+    let mut changes: Vec<Change> = current
+        .iter()
+        .filter_map(|vm| match before.get(vm.id.as_str()) {
+            None => Some(Change::Added(vm.clone())),
+            Some(old) if *old != vm => Some(Change::Updated {
+                vm: vm.clone(),
+                from: old.status.clone(),
+            }),
+            Some(_) => None,
+        })
+        .collect();
+    changes.extend(
+        previous
+            .iter()
+            .filter(|vm| !after.contains_key(vm.id.as_str()))
+            .map(|vm| Change::Removed(vm.clone())),
+    );
+
+    changes
+}
+
+fn describe(change: &Change) -> String {
+    let time = Local::now().format("%H:%M:%S").to_string().dimmed();
+    match change {
+        Change::Added(vm) => format!("{} {} {}", time, vm.name.bold(), "appeared".green()),
+        Change::Updated { vm, from } if *from != vm.status => format!(
+            "{} {} {} {} {}",
+            time,
+            vm.name.bold(),
+            format_status(from),
+            "→".yellow().bold(),
+            format_status(&vm.status)
+        ),
+        Change::Updated { vm, .. } => format!("{} {} {}", time, vm.name.bold(), "updated".cyan()),
+        Change::Removed(vm) => format!("{} {} {}", time, vm.name.bold(), "deleted".red()),
+    }
+}
+
+/// A failed poll is reported and retried on the next tick; the server may
+/// just be restarting.
+fn warn(renderer: &Renderer<'_>, err: VpsError) {
+    say!(
+        renderer.output,
+        "{} {:#}",
+        "⚠️  Poll failed:".yellow(),
+        anyhow::Error::new(err)
+    );
+}
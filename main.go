@@ -2,13 +2,19 @@ package main
 
 import (
 	"context"
+	"crypto/sha256"
+	"encoding/json"
+	"errors"
 	"fmt"
 	"io"
 	"log"
+	"net"
 	"net/http"
 	"os"
 	"os/exec"
 	"path/filepath"
+	"regexp"
+	"sort"
 	"strconv"
 	"sync"
 	"syscall"
@@ -16,6 +22,7 @@ import (
 
 	"github.com/firecracker-microvm/firecracker-go-sdk"
 	"github.com/firecracker-microvm/firecracker-go-sdk/client/models"
+	ops "github.com/firecracker-microvm/firecracker-go-sdk/client/operations"
 	"github.com/gin-contrib/cors"
 	"github.com/gin-gonic/gin"
 	"github.com/google/uuid"
@@ -24,43 +31,124 @@ import (
 
 // VM represents a virtual machine instance
 type VM struct {
-	ID         string    `json:"id"`
-	Name       string    `json:"name"`
-	CPU        int       `json:"cpu"`
-	Memory     int       `json:"memory"`    // MB
-	DiskSize   int       `json:"disk_size"` // GB
-	Image      string    `json:"image"`
-	Status     string    `json:"status"`
-	IPAddress  string    `json:"ip_address"`
-	CreatedAt  time.Time `json:"created_at"`
-	SocketPath string    `json:"socket_path"`
-	KernelPath string    `json:"kernel_path"`
-	RootfsPath string    `json:"rootfs_path"`
-	TapDevice  string    `json:"tap_device"`
-	machine    *firecracker.Machine
+	ID          string            `json:"id"`
+	Name        string            `json:"name"`
+	Description string            `json:"description"`
+	Labels      map[string]string `json:"labels"`
+	CPU         int               `json:"cpu"`
+	Memory      int               `json:"memory"`    // MB
+	DiskSize    int               `json:"disk_size"` // GB
+	Image       string            `json:"image"`
+	Status      string            `json:"status"`
+	IPAddress   string            `json:"ip_address"`
+	CreatedAt   time.Time         `json:"created_at"`
+	SocketPath  string            `json:"socket_path"`
+	KernelPath  string            `json:"kernel_path"`
+	RootfsPath  string            `json:"rootfs_path"`
+	TapDevice   string            `json:"tap_device"`
+	machine     *firecracker.Machine
+	// diffBase is the snapshot taken or restored last by the current
+	// Firecracker process. Dirty pages are only tracked since then, so
+	// a diff snapshot is only valid on top of its memory file.
+	diffBase    *Snapshot
 }
 
 // VMRequest represents a VM creation request
 type VMRequest struct {
-	Name     string `json:"name" binding:"required"`
-	CPU      int    `json:"cpu" binding:"required,min=1,max=8"`
-	Memory   int    `json:"memory" binding:"required,min=128,max=8192"`
-	DiskSize int    `json:"disk_size" binding:"required,min=1,max=100"`
-	Image    string `json:"image" binding:"required"`
+	Name     string            `json:"name" binding:"required"`
+	CPU      int               `json:"cpu" binding:"required,min=1,max=8"`
+	Memory   int               `json:"memory" binding:"required,min=128,max=8192"`
+	DiskSize int               `json:"disk_size" binding:"required,min=1,max=100"`
+	Image    string            `json:"image" binding:"required"`
+	Labels   map[string]string `json:"labels"`
+}
+
+var (
+	// Label keys may carry a prefix, e.g. example.com/team
+	labelKeyPattern   = regexp.MustCompile(`^[A-Za-z0-9]([A-Za-z0-9._/-]{0,61}[A-Za-z0-9])?$`)
+	labelValuePattern = regexp.MustCompile(`^([A-Za-z0-9]([A-Za-z0-9._-]{0,61}[A-Za-z0-9])?)?$`)
+)
+
+// validateLabels checks label keys and values: up to 63 alphanumerics,
+// '-', '_' and '.' (keys also '/'), starting and ending with an
+// alphanumeric. Values may be empty.
+func validateLabels(labels map[string]string) error {
+	for key, value := range labels {
+		if !labelKeyPattern.MatchString(key) {
+			return invalid("invalid label key '%s'", key)
+		}
+		if !labelValuePattern.MatchString(value) {
+			return invalid("invalid value '%s' for label '%s'", value, key)
+		}
+	}
+	return nil
+}
+
+// Snapshot is a point-in-time copy of a VM's memory, VM state and disk
+type Snapshot struct {
+	ID        string    `json:"id"`
+	VMID      string    `json:"vm_id"`
+	Type      string    `json:"type"` // full or diff
+	Size      int64     `json:"size"` // bytes
+	CreatedAt time.Time `json:"created_at"`
+	dir       string
+}
+
+// CloneRequest represents a VM clone request. The clone keeps the source's
+// image and disk size, and its CPU and memory unless given.
+type CloneRequest struct {
+	Name       string `json:"name" binding:"required"`
+	CPU        int    `json:"cpu" binding:"omitempty,min=1,max=8"`
+	Memory     int    `json:"memory" binding:"omitempty,min=128,max=8192"`
+	SnapshotID string `json:"snapshot_id"`
+}
+
+// ResizeRequest represents a VM resize request. Unset fields are kept.
+type ResizeRequest struct {
+	CPU      int `json:"cpu" binding:"omitempty,min=1,max=8"`
+	Memory   int `json:"memory" binding:"omitempty,min=128,max=8192"`
+	DiskSize int `json:"disk_size" binding:"omitempty,min=1,max=100"`
+}
+
+// UpdateRequest represents a VM metadata update. Only the fields present
+// are changed; labels are replaced as a whole.
+type UpdateRequest struct {
+	Name        *string           `json:"name"`
+	Description *string           `json:"description"`
+	Labels      map[string]string `json:"labels"`
+}
+
+// SnapshotRequest represents a snapshot creation request
+type SnapshotRequest struct {
+	Type string `json:"type" binding:"omitempty,oneof=full diff"`
 }
 
 // VMManager manages all VM instances
 type VMManager struct {
-	vms        map[string]*VM
-	mutex      sync.RWMutex
-	config     *Config
-	ipPool     *IPPool
-	tapManager *TapManager
+	vms             map[string]*VM
+	snapshots       map[string]*Snapshot
+	mutex           sync.RWMutex
+	config          *Config
+	ipPool          *IPPool
+	tapManager      *TapManager
+	idempotencyKeys map[string]idempotencyEntry // by Idempotency-Key header
+}
+
+// idempotencyTTL is how long an Idempotency-Key is remembered.
+const idempotencyTTL = 24 * time.Hour
+
+// idempotencyEntry is the VM created for an Idempotency-Key, along with a
+// hash of the request that created it.
+type idempotencyEntry struct {
+	vmID        string
+	requestHash [sha256.Size]byte
+	expiresAt   time.Time
 }
 
 // Config holds application configuration
 type Config struct {
 	APIPort       string
+	APISocket     string // serve on this Unix socket instead of APIPort
 	VMDir         string
 	KernelPath    string
 	BaseImagesDir string
@@ -89,9 +177,48 @@ type Response struct {
 	Data    interface{} `json:"data,omitempty"`
 }
 
+// apiError is an error reported with an HTTP status other than 500.
+type apiError struct {
+	status  int
+	message string
+}
+
+func (e *apiError) Error() string {
+	return e.message
+}
+
+func notFound(format string, args ...interface{}) error {
+	return &apiError{http.StatusNotFound, fmt.Sprintf(format, args...)}
+}
+
+// conflict is for a VM in the wrong state for the request, or a name
+// that is taken.
+func conflict(format string, args ...interface{}) error {
+	return &apiError{http.StatusConflict, fmt.Sprintf(format, args...)}
+}
+
+func invalid(format string, args ...interface{}) error {
+	return &apiError{http.StatusBadRequest, fmt.Sprintf(format, args...)}
+}
+
+// noCapacity is for a host out of IP addresses or disk space.
+func noCapacity(format string, args ...interface{}) error {
+	return &apiError{http.StatusInsufficientStorage, fmt.Sprintf(format, args...)}
+}
+
+// errorStatus is the HTTP status to report err with.
+func errorStatus(err error) int {
+	var apiErr *apiError
+	if errors.As(err, &apiErr) {
+		return apiErr.status
+	}
+	return http.StatusInternalServerError
+}
+
 func NewConfig() *Config {
 	return &Config{
 		APIPort:       getEnvOrDefault("API_PORT", "8080"),
+		APISocket:     getEnvOrDefault("API_SOCKET", ""),
 		VMDir:         getEnvOrDefault("VM_DIR", "/var/lib/firecracker-vms"),
 		KernelPath:    getEnvOrDefault("KERNEL_PATH", "/var/lib/firecracker/vmlinux.bin"),
 		BaseImagesDir: getEnvOrDefault("BASE_IMAGES_DIR", "/var/lib/firecracker/images"),
@@ -119,10 +246,12 @@ func getEnvInt(key string, defaultValue int) int {
 
 func NewVMManager(config *Config) *VMManager {
 	return &VMManager{
-		vms:        make(map[string]*VM),
-		config:     config,
-		ipPool:     NewIPPool(config.NetworkSubnet),
-		tapManager: NewTapManager(),
+		vms:             make(map[string]*VM),
+		snapshots:       make(map[string]*Snapshot),
+		config:          config,
+		ipPool:          NewIPPool(config.NetworkSubnet),
+		tapManager:      NewTapManager(),
+		idempotencyKeys: make(map[string]idempotencyEntry),
 	}
 }
 
@@ -175,17 +304,148 @@ func (tm *TapManager) ReleaseTap(tapName string) {
 	delete(tm.tapDevices, tapName)
 }
 
-func (vmm *VMManager) CreateVM(req VMRequest) (*VM, error) {
+func (vmm *VMManager) CreateVM(req VMRequest, idempotencyKey string) (*VM, error) {
 	vmm.mutex.Lock()
 	defer vmm.mutex.Unlock()
 
+	// A retried request returns the VM created by the first attempt
+	hash := requestHash("create", req)
+	if vm, err := vmm.replay(idempotencyKey, hash); vm != nil || err != nil {
+		return vm, err
+	}
+
+	if err := validateLabels(req.Labels); err != nil {
+		return nil, err
+	}
+
+	baseImagePath := filepath.Join(vmm.config.BaseImagesDir, req.Image+".ext4")
+	vm, err := vmm.provisionVM(req, baseImagePath)
+	if err != nil {
+		return nil, err
+	}
+
+	vmm.remember(idempotencyKey, hash, vm)
+	return vm, nil
+}
+
+// requestHash identifies a create or clone request, so that an
+// Idempotency-Key reused for a different one can be told apart.
+func requestHash(kind string, req interface{}) [sha256.Size]byte {
+	body, _ := json.Marshal(req)
+	return sha256.Sum256(append([]byte(kind+"\n"), body...))
+}
+
+// replay returns the VM a retried request created on its first attempt,
+// or nil if the key is new or its VM was deleted since. A key reused for a
+// different request is refused. Expired keys are evicted. The caller holds
+// the lock.
+func (vmm *VMManager) replay(key string, hash [sha256.Size]byte) (*VM, error) {
+	now := time.Now()
+	for k, entry := range vmm.idempotencyKeys {
+		if now.After(entry.expiresAt) {
+			delete(vmm.idempotencyKeys, k)
+		}
+	}
+
+	entry, ok := vmm.idempotencyKeys[key]
+	if key == "" || !ok {
+		return nil, nil
+	}
+	if entry.requestHash != hash {
+		return nil, &apiError{http.StatusUnprocessableEntity, "Idempotency-Key was already used for a different request"}
+	}
+	return vmm.vms[entry.vmID], nil
+}
+
+// remember records the VM created for an Idempotency-Key. The caller holds
+// the lock.
+func (vmm *VMManager) remember(key string, hash [sha256.Size]byte, vm *VM) {
+	if key != "" {
+		vmm.idempotencyKeys[key] = idempotencyEntry{
+			vmID:        vm.ID,
+			requestHash: hash,
+			expiresAt:   time.Now().Add(idempotencyTTL),
+		}
+	}
+}
+
+// CloneVM creates a VM with a copy of another VM's disk, or of the disk
+// saved in one of its snapshots. The clone gets its own IP address and TAP
+// device, keeps the source's labels and starts out in the created state.
+// A running source is paused while its disk is copied, so the copy is
+// consistent.
+func (vmm *VMManager) CloneVM(sourceID string, req CloneRequest, idempotencyKey string) (*VM, error) {
+	vmm.mutex.Lock()
+	defer vmm.mutex.Unlock()
+
+	// A retried request returns the VM created by the first attempt
+	hash := requestHash("clone "+sourceID, req)
+	if vm, err := vmm.replay(idempotencyKey, hash); vm != nil || err != nil {
+		return vm, err
+	}
+
+	source, exists := vmm.vms[sourceID]
+	if !exists {
+		return nil, notFound("VM not found")
+	}
+
+	if err := source.busy(); err != nil {
+		return nil, err
+	}
+
+	sourceRootfs := source.RootfsPath
+	if req.SnapshotID != "" {
+		snapshot, exists := vmm.snapshots[req.SnapshotID]
+		if !exists || snapshot.VMID != sourceID {
+			return nil, notFound("snapshot not found")
+		}
+		sourceRootfs = filepath.Join(snapshot.dir, "rootfs.ext4")
+	} else if source.Status == "running" && source.machine != nil {
+		ctx := context.Background()
+		if err := source.machine.PauseVM(ctx); err != nil {
+			return nil, fmt.Errorf("failed to pause source VM: %v", err)
+		}
+		defer source.machine.ResumeVM(ctx)
+	}
+
+	if req.CPU == 0 {
+		req.CPU = source.CPU
+	}
+	if req.Memory == 0 {
+		req.Memory = source.Memory
+	}
+
+	vm, err := vmm.provisionVM(VMRequest{
+		Name:     req.Name,
+		CPU:      req.CPU,
+		Memory:   req.Memory,
+		DiskSize: source.DiskSize,
+		Image:    source.Image,
+		Labels:   source.Labels,
+	}, sourceRootfs)
+	if err != nil {
+		return nil, err
+	}
+
+	vmm.remember(idempotencyKey, hash, vm)
+	return vm, nil
+}
+
+// provisionVM allocates an ID, IP address and TAP device for a new VM and
+// builds its rootfs from sourceImage. The caller holds the lock.
+func (vmm *VMManager) provisionVM(req VMRequest, sourceImage string) (*VM, error) {
 	// Generate unique VM ID
 	vmID := uuid.New().String()
 
+	labels := make(map[string]string, len(req.Labels))
+	for key, value := range req.Labels {
+		labels[key] = value
+	}
+
 	// Allocate resources
 	ipAddr := vmm.ipPool.AllocateIP()
 	if ipAddr == "" {
-		return nil, fmt.Errorf("no available IP addresses")
+		return nil, noCapacity("no available IP addresses")
 	}
 
 	tapDevice := vmm.tapManager.AllocateTap(vmID)
@@ -199,13 +459,12 @@ func (vmm *VMManager) CreateVM(req VMRequest) (*VM, error) {
 		return nil, fmt.Errorf("failed to create VM directory: %v", err)
 	}
 
-	// Create VM rootfs from base image
-	baseImagePath := filepath.Join(vmm.config.BaseImagesDir, req.Image+".ext4")
+	// Create VM rootfs from the base image or source disk
 	rootfsPath := filepath.Join(vmDir, "rootfs.ext4")
 
-	if err := vmm.createVMRootfs(baseImagePath, rootfsPath, req.DiskSize); err != nil {
+	if err := vmm.createVMRootfs(sourceImage, rootfsPath, req.DiskSize); err != nil {
 		vmm.cleanup(vmID, ipAddr, tapDevice)
-		return nil, fmt.Errorf("failed to create VM rootfs: %v", err)
+		return nil, fmt.Errorf("failed to create VM rootfs: %w", err)
 	}
 
 	// Create TAP interface
@@ -217,6 +476,7 @@ func (vmm *VMManager) CreateVM(req VMRequest) (*VM, error) {
 	vm := &VM{
 		ID:         vmID,
 		Name:       req.Name,
+		Labels:     labels,
 		CPU:        req.CPU,
 		Memory:     req.Memory,
 		DiskSize:   req.DiskSize,
@@ -234,19 +494,35 @@ func (vmm *VMManager) CreateVM(req VMRequest) (*VM, error) {
 	return vm, nil
 }
 
+// StartVM boots a VM.
 func (vmm *VMManager) StartVM(vmID string) error {
 	vmm.mutex.Lock()
 	defer vmm.mutex.Unlock()
 
 	vm, exists := vmm.vms[vmID]
 	if !exists {
-		return fmt.Errorf("VM not found")
+		return notFound("VM not found")
+	}
+
+	if err := vm.busy(); err != nil {
+		return err
 	}
 
 	if vm.Status == "running" {
-		return fmt.Errorf("VM is already running")
+		return conflict("VM is already running")
+	}
+
+	if vm.Status == "paused" {
+		return conflict("VM is paused, resume it instead")
 	}
 
+	return vmm.boot(vm)
+}
+
+// boot starts a Firecracker process for a stopped VM. Extra options, such
+// as loading a snapshot, are passed on to the Firecracker machine. The
+// caller holds the lock.
+func (vmm *VMManager) boot(vm *VM, extraOpts ...firecracker.Opt) error {
 	// Configure Firecracker
 	// TODO: decide on CNI or Static network config (disabled CNI config)
 	cfg := firecracker.Config{
@@ -272,8 +548,9 @@ func (vmm *VMManager) StartVM(vmID string) error {
 			},
 		}},
 		MachineCfg: models.MachineConfiguration{
-			VcpuCount:  firecracker.Int64(int64(vm.CPU)),
-			MemSizeMib: firecracker.Int64(int64(vm.Memory)),
+			VcpuCount:       firecracker.Int64(int64(vm.CPU)),
+			MemSizeMib:      firecracker.Int64(int64(vm.Memory)),
+			TrackDirtyPages: true, // needed for diff snapshots
 		},
 		// JailerCfg: &firecracker.JailerConfig{
 		// 	GID:           firecracker.Int(1000),
@@ -311,6 +588,7 @@ func (vmm *VMManager) StartVM(vmID string) error {
 			firecracker.VMCommandBuilder{}.WithSocketPath(vm.SocketPath).WithBin(filepath.Join(dir, "firecracker")).Build(ctx),
 		),
 	}
+	opts = append(opts, extraOpts...)
 
 	// m, err := firecracker.NewMachine(ctx, cfg, firecracker.WithLogger(logger.WithContext(ctx)))
 	m, err := firecracker.NewMachine(ctx, cfg, opts...)
@@ -324,32 +602,215 @@ func (vmm *VMManager) StartVM(vmID string) error {
 
 	vm.machine = m
 	vm.Status = "running"
+	vm.diffBase = nil
 
 	return nil
 }
 
+// StopVM shuts a VM down and returns once its Firecracker process has
+// exited, so the VM can be started, restored or resized right away.
 func (vmm *VMManager) StopVM(vmID string) error {
 	vmm.mutex.Lock()
 	defer vmm.mutex.Unlock()
 
 	vm, exists := vmm.vms[vmID]
 	if !exists {
-		return fmt.Errorf("VM not found")
+		return notFound("VM not found")
+	}
+
+	if err := vm.busy(); err != nil {
+		return err
 	}
 
-	if vm.Status != "running" {
-		return fmt.Errorf("VM is not running")
+	if vm.Status != "running" && vm.Status != "paused" {
+		return conflict("VM is not running")
 	}
 
-	if vm.machine != nil {
-		if err := vm.machine.Shutdown(context.Background()); err != nil {
-			return fmt.Errorf("failed to shutdown VM: %v", err)
+	return vmm.stopMachine(vm)
+}
+
+// busy refuses lifecycle operations on a VM that is in the middle of
+// stopping, rebooting, or taking or restoring a snapshot, while its
+// Firecracker process or files are in use without the lock held.
+func (vm *VM) busy() error {
+	switch vm.Status {
+	case "stopping", "rebooting", "snapshotting", "restoring":
+		return conflict("VM is %s, try again once it is done", vm.Status)
+	}
+	return nil
+}
+
+// stopMachine marks a running or paused VM as stopping and shuts its
+// Firecracker process down. The caller holds the lock; it is released
+// while waiting for the guest, so other requests are served meanwhile,
+// and held again on return. If the process does not exit, the VM keeps
+// its previous status.
+func (vmm *VMManager) stopMachine(vm *VM) error {
+	previous, machine := vm.Status, vm.machine
+	if machine != nil {
+		vm.Status = "stopping"
+		vmm.mutex.Unlock()
+		err := shutdownMachine(machine, previous == "paused")
+		vmm.mutex.Lock()
+		if err != nil {
+			vm.Status = previous
+			return err
 		}
 	}
 
 	vm.Status = "stopped"
 	vm.machine = nil
+	return nil
+}
+
+// stopTimeout is how long a guest gets to shut down before its Firecracker
+// process is killed.
+const stopTimeout = 30 * time.Second
+
+// shutdownMachine sends Ctrl+Alt+Del and waits for the Firecracker process
+// to exit, as it does once the guest has shut down. A guest that does not
+// shut down within stopTimeout, or a paused one that cannot be resumed to
+// react, is stopped with StopVMM. Only once the process is gone are its API
+// socket, TAP device and rootfs free for the next start, restore or resize.
+func shutdownMachine(machine *firecracker.Machine, paused bool) error {
+	// A paused guest cannot react to Ctrl+Alt+Del
+	resumed := !paused || machine.ResumeVM(context.Background()) == nil
+	if resumed {
+		if err := machine.Shutdown(context.Background()); err == nil {
+			ctx, cancel := context.WithTimeout(context.Background(), stopTimeout)
+			defer cancel()
+			if err := machine.Wait(ctx); err == nil || ctx.Err() == nil {
+				return nil
+			}
+		}
+	}
+
+	if err := machine.StopVMM(); err != nil {
+		return fmt.Errorf("failed to stop VMM: %v", err)
+	}
 
+	ctx, cancel := context.WithTimeout(context.Background(), 10*time.Second)
+	defer cancel()
+	if err := machine.Wait(ctx); err != nil && ctx.Err() != nil {
+		return fmt.Errorf("firecracker process did not exit: %v", ctx.Err())
+	}
+	return nil
+}
+
+// PauseVM freezes the vCPUs of a running VM. Memory and devices keep their
+// state until the VM is resumed.
+func (vmm *VMManager) PauseVM(vmID string) error {
+	vmm.mutex.Lock()
+	defer vmm.mutex.Unlock()
+
+	vm, exists := vmm.vms[vmID]
+	if !exists {
+		return notFound("VM not found")
+	}
+
+	if vm.Status == "paused" {
+		return conflict("VM is already paused")
+	}
+
+	if vm.Status != "running" || vm.machine == nil {
+		return conflict("VM is not running")
+	}
+
+	if err := vm.machine.PauseVM(context.Background()); err != nil {
+		return fmt.Errorf("failed to pause VM: %v", err)
+	}
+
+	vm.Status = "paused"
+
+	return nil
+}
+
+// ResumeVM lets a paused VM run again.
+func (vmm *VMManager) ResumeVM(vmID string) error {
+	vmm.mutex.Lock()
+	defer vmm.mutex.Unlock()
+
+	vm, exists := vmm.vms[vmID]
+	if !exists {
+		return notFound("VM not found")
+	}
+
+	if vm.Status != "paused" || vm.machine == nil {
+		return conflict("VM is not paused")
+	}
+
+	if err := vm.machine.ResumeVM(context.Background()); err != nil {
+		return fmt.Errorf("failed to resume VM: %v", err)
+	}
+
+	vm.Status = "running"
+
+	return nil
+}
+
+// RebootVM asks the guest to reboot itself by sending Ctrl+Alt+Del. The
+// Firecracker process exits once the guest has shut down, so the VM is
+// booted again from the same configuration afterwards. The VM has the
+// status rebooting meanwhile, and the lock is not held while waiting.
+func (vmm *VMManager) RebootVM(vmID string) error {
+	vmm.mutex.Lock()
+	vm, exists := vmm.vms[vmID]
+	if !exists {
+		vmm.mutex.Unlock()
+		return notFound("VM not found")
+	}
+
+	if err := vm.busy(); err != nil {
+		vmm.mutex.Unlock()
+		return err
+	}
+
+	if vm.Status != "running" || vm.machine == nil {
+		vmm.mutex.Unlock()
+		return conflict("VM is not running")
+	}
+	machine := vm.machine
+	socketPath := vm.SocketPath
+	vm.Status = "rebooting"
+	vmm.mutex.Unlock()
+
+	err := waitForReboot(machine, socketPath)
+
+	vmm.mutex.Lock()
+	defer vmm.mutex.Unlock()
+
+	// Nothing else acts on a rebooting VM, but make sure it is still the
+	// same one before booting it again
+	if current, exists := vmm.vms[vmID]; !exists || current != vm || vm.Status != "rebooting" {
+		return conflict("VM changed while rebooting, not starting it again")
+	}
+	if err != nil {
+		// The guest did not go down, so it is still running
+		vm.Status = "running"
+		return err
+	}
+
+	vm.Status = "stopped"
+	vm.machine = nil
+	return vmm.boot(vm)
+}
+
+// waitForReboot sends Ctrl+Alt+Del and waits for the Firecracker process
+// to exit as the guest shuts down.
+func waitForReboot(machine *firecracker.Machine, socketPath string) error {
+	client := firecracker.NewClient(socketPath, logrus.NewEntry(logrus.StandardLogger()), false)
+	action := &models.InstanceActionInfo{
+		ActionType: firecracker.String(models.InstanceActionInfoActionTypeSendCtrlAltDel),
+	}
+	if _, err := client.CreateSyncAction(context.Background(), action); err != nil {
+		return fmt.Errorf("failed to send Ctrl+Alt+Del: %v", err)
+	}
+
+	ctx, cancel := context.WithTimeout(context.Background(), 60*time.Second)
+	defer cancel()
+	if err := machine.Wait(ctx); err != nil && ctx.Err() != nil {
+		return fmt.Errorf("guest did not shut down for reboot: %v", ctx.Err())
+	}
 	return nil
 }
 
@@ -359,14 +820,16 @@ func (vmm *VMManager) DeleteVM(vmID string) error {
 
 	vm, exists := vmm.vms[vmID]
 	if !exists {
-		return fmt.Errorf("VM not found")
+		return notFound("VM not found")
 	}
 
-	// Stop VM if running
-	if vm.Status == "running" && vm.machine != nil {
-		vm.machine.Shutdown(context.Background())
+	if err := vm.busy(); err != nil {
+		return err
 	}
 
+	// Stop VM if running, before its files are removed; they go either way
+	vmm.stopMachine(vm)
+
 	// Cleanup resources
 	vmm.ipPool.ReleaseIP(vm.IPAddress)
 	vmm.tapManager.ReleaseTap(vm.TapDevice)
@@ -378,17 +841,330 @@ func (vmm *VMManager) DeleteVM(vmID string) error {
 	vmDir := filepath.Dir(vm.SocketPath)
 	os.RemoveAll(vmDir)
 
+	for id, snapshot := range vmm.snapshots {
+		if snapshot.VMID == vmID {
+			delete(vmm.snapshots, id)
+		}
+	}
+
 	delete(vmm.vms, vmID)
 	return nil
 }
 
+// UpdateVM changes the name, description or labels of a VM. A name
+// another VM already has is refused, so that names keep referring to a
+// single VM.
+func (vmm *VMManager) UpdateVM(vmID string, req UpdateRequest) (*VM, error) {
+	vmm.mutex.Lock()
+	defer vmm.mutex.Unlock()
+
+	vm, exists := vmm.vms[vmID]
+	if !exists {
+		return nil, notFound("VM not found")
+	}
+
+	if err := validateLabels(req.Labels); err != nil {
+		return nil, err
+	}
+
+	if req.Name != nil {
+		if *req.Name == "" {
+			return nil, invalid("name cannot be empty")
+		}
+		for id, other := range vmm.vms {
+			if id != vmID && other.Name == *req.Name {
+				return nil, conflict("VM name '%s' is already in use", *req.Name)
+			}
+		}
+		vm.Name = *req.Name
+	}
+	if req.Description != nil {
+		vm.Description = *req.Description
+	}
+	if req.Labels != nil {
+		vm.Labels = req.Labels
+	}
+
+	return vm, nil
+}
+
+// ResizeVM changes the CPU count, memory and disk size of a stopped VM.
+// Firecracker cannot hot-plug vCPUs or memory, and the filesystem is
+// grown offline. Disks can only grow.
+func (vmm *VMManager) ResizeVM(vmID string, req ResizeRequest) (*VM, error) {
+	vmm.mutex.Lock()
+	defer vmm.mutex.Unlock()
+
+	vm, exists := vmm.vms[vmID]
+	if !exists {
+		return nil, notFound("VM not found")
+	}
+
+	// The rootfs is checked and grown in place, which is only safe once the
+	// Firecracker process is gone; StopVM clears machine after it exits
+	if vm.Status == "running" || vm.Status == "paused" || vm.machine != nil {
+		return nil, conflict("VM must be stopped to resize")
+	}
+
+	if req.DiskSize != 0 && req.DiskSize < vm.DiskSize {
+		return nil, invalid("disk cannot shrink from %dGB to %dGB", vm.DiskSize, req.DiskSize)
+	}
+
+	if req.DiskSize > vm.DiskSize {
+		if err := vmm.checkDiskSpace(filepath.Dir(vm.RootfsPath), req.DiskSize-vm.DiskSize); err != nil {
+			return nil, noCapacity("insufficient disk space: %v", err)
+		}
+		if err := vmm.resizeRootfs(vm.RootfsPath, req.DiskSize); err != nil {
+			return nil, fmt.Errorf("failed to resize rootfs: %v", err)
+		}
+		vm.DiskSize = req.DiskSize
+	}
+
+	if req.CPU != 0 {
+		vm.CPU = req.CPU
+	}
+	if req.Memory != 0 {
+		vm.Memory = req.Memory
+	}
+
+	return vm, nil
+}
+
+// CreateSnapshot saves the memory and VM state of a running or paused VM,
+// along with a copy of its disk. A diff snapshot only writes the memory
+// pages changed since the snapshot taken or restored last by the VM's
+// current Firecracker process, on top of a copy of that snapshot's memory
+// file, so it can be restored on its own. The files are written without
+// the lock held; the VM has the status snapshotting meanwhile.
+func (vmm *VMManager) CreateSnapshot(vmID, snapshotType string) (*Snapshot, error) {
+	vmm.mutex.Lock()
+	vm, exists := vmm.vms[vmID]
+	if !exists {
+		vmm.mutex.Unlock()
+		return nil, notFound("VM not found")
+	}
+
+	if err := vm.busy(); err != nil {
+		vmm.mutex.Unlock()
+		return nil, err
+	}
+
+	if (vm.Status != "running" && vm.Status != "paused") || vm.machine == nil {
+		vmm.mutex.Unlock()
+		return nil, conflict("VM is not running")
+	}
+
+	if snapshotType == "" {
+		snapshotType = "full"
+	}
+
+	var base *Snapshot
+	if snapshotType == "diff" {
+		base = vm.diffBase
+		if base == nil {
+			vmm.mutex.Unlock()
+			return nil, invalid("a diff snapshot needs a snapshot taken or restored since the VM was last started")
+		}
+	}
+
+	previous := vm.Status
+	vm.Status = "snapshotting"
+	vmm.mutex.Unlock()
+
+	snapshot, err := vmm.takeSnapshot(vm, previous == "running", snapshotType, base)
+
+	vmm.mutex.Lock()
+	defer vmm.mutex.Unlock()
+	vm.Status = previous
+	if err != nil {
+		return nil, err
+	}
+
+	vmm.snapshots[snapshot.ID] = snapshot
+	vm.diffBase = snapshot
+
+	return snapshot, nil
+}
+
+// takeSnapshot writes the files of a new snapshot of vm, pausing it for as
+// long as that takes if it is running. A diff snapshot starts from a copy
+// of base's memory file. Runs without the lock; CreateSnapshot marks the
+// VM as snapshotting so nothing else touches it.
+func (vmm *VMManager) takeSnapshot(vm *VM, running bool, snapshotType string, base *Snapshot) (*Snapshot, error) {
+	snapshotID := uuid.New().String()
+	dir := filepath.Join(filepath.Dir(vm.SocketPath), "snapshots", snapshotID)
+	if err := os.MkdirAll(dir, 0755); err != nil {
+		return nil, fmt.Errorf("failed to create snapshot directory: %v", err)
+	}
+	memPath := filepath.Join(dir, "memory")
+
+	if base != nil {
+		if err := vmm.copyFileWithProgress(filepath.Join(base.dir, "memory"), memPath); err != nil {
+			os.RemoveAll(dir)
+			return nil, fmt.Errorf("failed to copy base memory file: %v", err)
+		}
+	}
+
+	// Firecracker only snapshots paused VMs
+	ctx := context.Background()
+	if running {
+		if err := vm.machine.PauseVM(ctx); err != nil {
+			os.RemoveAll(dir)
+			return nil, fmt.Errorf("failed to pause VM: %v", err)
+		}
+		defer vm.machine.ResumeVM(ctx)
+	}
+
+	var opts []firecracker.CreateSnapshotOpt
+	if snapshotType == "diff" {
+		opts = append(opts, func(params *ops.CreateSnapshotParams) {
+			params.Body.SnapshotType = models.SnapshotCreateParamsSnapshotTypeDiff
+		})
+	}
+	if err := vm.machine.CreateSnapshot(ctx, memPath, filepath.Join(dir, "vmstate"), opts...); err != nil {
+		os.RemoveAll(dir)
+		return nil, fmt.Errorf("failed to create snapshot: %v", err)
+	}
+
+	// Memory snapshots do not cover the disk
+	if err := vmm.copyFileWithProgress(vm.RootfsPath, filepath.Join(dir, "rootfs.ext4")); err != nil {
+		os.RemoveAll(dir)
+		return nil, fmt.Errorf("failed to copy rootfs: %v", err)
+	}
+
+	return &Snapshot{
+		ID:        snapshotID,
+		VMID:      vm.ID,
+		Type:      snapshotType,
+		Size:      dirSize(dir),
+		CreatedAt: time.Now(),
+		dir:       dir,
+	}, nil
+}
+
+// ListSnapshots returns a VM's snapshots, oldest first.
+func (vmm *VMManager) ListSnapshots(vmID string) ([]*Snapshot, error) {
+	vmm.mutex.RLock()
+	defer vmm.mutex.RUnlock()
+
+	if _, exists := vmm.vms[vmID]; !exists {
+		return nil, notFound("VM not found")
+	}
+
+	snapshots := make([]*Snapshot, 0)
+	for _, snapshot := range vmm.snapshots {
+		if snapshot.VMID == vmID {
+			snapshots = append(snapshots, snapshot)
+		}
+	}
+	sort.Slice(snapshots, func(i, j int) bool {
+		return snapshots[i].CreatedAt.Before(snapshots[j].CreatedAt)
+	})
+
+	return snapshots, nil
+}
+
+// RestoreSnapshot puts back the snapshot's disk and boots the stopped VM
+// from its memory and VM state.
+func (vmm *VMManager) RestoreSnapshot(vmID, snapshotID string) error {
+	vmm.mutex.Lock()
+	vm, exists := vmm.vms[vmID]
+	if !exists {
+		vmm.mutex.Unlock()
+		return notFound("VM not found")
+	}
+
+	snapshot, exists := vmm.snapshots[snapshotID]
+	if !exists || snapshot.VMID != vmID {
+		vmm.mutex.Unlock()
+		return notFound("snapshot not found")
+	}
+
+	if err := vm.busy(); err != nil {
+		vmm.mutex.Unlock()
+		return err
+	}
+
+	// The snapshot's disk is copied over the rootfs and booted on the same
+	// socket, so the old Firecracker process must have exited
+	if vm.Status == "running" || vm.Status == "paused" || vm.machine != nil {
+		vmm.mutex.Unlock()
+		return conflict("VM must be stopped to restore a snapshot")
+	}
+
+	// Copy without the lock; restoring keeps the VM from being started
+	previous := vm.Status
+	vm.Status = "restoring"
+	vmm.mutex.Unlock()
+
+	err := vmm.copyFileWithProgress(filepath.Join(snapshot.dir, "rootfs.ext4"), vm.RootfsPath)
+
+	vmm.mutex.Lock()
+	defer vmm.mutex.Unlock()
+	vm.Status = previous
+	if err != nil {
+		return fmt.Errorf("failed to restore rootfs: %v", err)
+	}
+
+	err = vmm.boot(vm, firecracker.WithSnapshot(
+		filepath.Join(snapshot.dir, "memory"),
+		filepath.Join(snapshot.dir, "vmstate"),
+		func(cfg *firecracker.SnapshotConfig) {
+			cfg.ResumeVM = true
+			// Keep tracking dirty pages, for diff snapshots on top of this one
+			cfg.EnableDiffSnapshots = true
+		},
+	))
+	if err != nil {
+		return err
+	}
+	vm.diffBase = snapshot
+
+	return nil
+}
+
+// DeleteSnapshot removes a snapshot and its files.
+func (vmm *VMManager) DeleteSnapshot(vmID, snapshotID string) error {
+	vmm.mutex.Lock()
+	defer vmm.mutex.Unlock()
+
+	snapshot, exists := vmm.snapshots[snapshotID]
+	if !exists || snapshot.VMID != vmID {
+		return notFound("snapshot not found")
+	}
+
+	if err := os.RemoveAll(snapshot.dir); err != nil {
+		return fmt.Errorf("failed to remove snapshot files: %v", err)
+	}
+
+	// Its memory file is gone, so it can no longer be a diff base
+	if vm, exists := vmm.vms[vmID]; exists && vm.diffBase == snapshot {
+		vm.diffBase = nil
+	}
+
+	delete(vmm.snapshots, snapshotID)
+	return nil
+}
+
+// dirSize adds up the sizes of the files under dir.
+func dirSize(dir string) int64 {
+	var size int64
+	filepath.Walk(dir, func(_ string, info os.FileInfo, err error) error {
+		if err == nil && !info.IsDir() {
+			size += info.Size()
+		}
+		return nil
+	})
+	return size
+}
+
 func (vmm *VMManager) GetVM(vmID string) (*VM, error) {
 	vmm.mutex.RLock()
 	defer vmm.mutex.RUnlock()
 
 	vm, exists := vmm.vms[vmID]
 	if !exists {
-		return nil, fmt.Errorf("VM not found")
+		return nil, notFound("VM not found")
 	}
 
 	return vm, nil
@@ -412,7 +1188,7 @@ func (vmm *VMManager) createVMRootfs(baseImage, rootfsPath string, sizeGB int) e
 	logrus.Println("Base Image: ", baseImage)
 	sourceInfo, err := os.Stat(baseImage)
 	if os.IsNotExist(err) {
-		return fmt.Errorf("base image not found: %s", baseImage)
+		return invalid("base image not found: %s", baseImage)
 	}
 	if err != nil {
 		return fmt.Errorf("cannot access base image %s: %v", baseImage, err)
@@ -450,7 +1226,7 @@ func (vmm *VMManager) createVMRootfs(baseImage, rootfsPath string, sizeGB int) e
 
 	// 6. Check available disk space
 	if err := vmm.checkDiskSpace(destDir, sizeGB); err != nil {
-		return fmt.Errorf("insufficient disk space: %v", err)
+		return noCapacity("insufficient disk space: %v", err)
 	}
 
 	log.Printf("Copying base image: %s -> %s (source size: %d bytes)",
@@ -641,9 +1417,9 @@ func (vmm *VMManager) createVMHandler(c *gin.Context) {
 		return
 	}
 
-	vm, err := vmm.CreateVM(req)
+	vm, err := vmm.CreateVM(req, c.GetHeader("Idempotency-Key"))
 	if err != nil {
-		c.JSON(http.StatusInternalServerError, Response{
+		c.JSON(errorStatus(err), Response{
 			Success: false,
 			Message: fmt.Sprintf("Failed to create VM: %v", err),
 		})
@@ -657,6 +1433,32 @@ func (vmm *VMManager) createVMHandler(c *gin.Context) {
 	})
 }
 
+func (vmm *VMManager) cloneVMHandler(c *gin.Context) {
+	var req CloneRequest
+	if err := c.ShouldBindJSON(&req); err != nil {
+		c.JSON(http.StatusBadRequest, Response{
+			Success: false,
+			Message: fmt.Sprintf("Invalid request: %v", err),
+		})
+		return
+	}
+
+	vm, err := vmm.CloneVM(c.Param("id"), req, c.GetHeader("Idempotency-Key"))
+	if err != nil {
+		c.JSON(errorStatus(err), Response{
+			Success: false,
+			Message: fmt.Sprintf("Failed to clone VM: %v", err),
+		})
+		return
+	}
+
+	c.JSON(http.StatusCreated, Response{
+		Success: true,
+		Message: "VM cloned successfully",
+		Data:    vm,
+	})
+}
+
 func (vmm *VMManager) listVMsHandler(c *gin.Context) {
 	vms := vmm.ListVMs()
 	c.JSON(http.StatusOK, Response{
@@ -687,7 +1489,7 @@ func (vmm *VMManager) getVMHandler(c *gin.Context) {
 func (vmm *VMManager) startVMHandler(c *gin.Context) {
 	vmID := c.Param("id")
 	if err := vmm.StartVM(vmID); err != nil {
-		c.JSON(http.StatusInternalServerError, Response{
+		c.JSON(errorStatus(err), Response{
 			Success: false,
 			Message: fmt.Sprintf("Failed to start VM: %v", err),
 		})
@@ -703,7 +1505,7 @@ func (vmm *VMManager) startVMHandler(c *gin.Context) {
 func (vmm *VMManager) stopVMHandler(c *gin.Context) {
 	vmID := c.Param("id")
 	if err := vmm.StopVM(vmID); err != nil {
-		c.JSON(http.StatusInternalServerError, Response{
+		c.JSON(errorStatus(err), Response{
 			Success: false,
 			Message: fmt.Sprintf("Failed to stop VM: %v", err),
 		})
@@ -716,10 +1518,58 @@ func (vmm *VMManager) stopVMHandler(c *gin.Context) {
 	})
 }
 
+func (vmm *VMManager) pauseVMHandler(c *gin.Context) {
+	vmID := c.Param("id")
+	if err := vmm.PauseVM(vmID); err != nil {
+		c.JSON(errorStatus(err), Response{
+			Success: false,
+			Message: fmt.Sprintf("Failed to pause VM: %v", err),
+		})
+		return
+	}
+
+	c.JSON(http.StatusOK, Response{
+		Success: true,
+		Message: "VM paused successfully",
+	})
+}
+
+func (vmm *VMManager) resumeVMHandler(c *gin.Context) {
+	vmID := c.Param("id")
+	if err := vmm.ResumeVM(vmID); err != nil {
+		c.JSON(errorStatus(err), Response{
+			Success: false,
+			Message: fmt.Sprintf("Failed to resume VM: %v", err),
+		})
+		return
+	}
+
+	c.JSON(http.StatusOK, Response{
+		Success: true,
+		Message: "VM resumed successfully",
+	})
+}
+
+func (vmm *VMManager) rebootVMHandler(c *gin.Context) {
+	vmID := c.Param("id")
+	if err := vmm.RebootVM(vmID); err != nil {
+		c.JSON(errorStatus(err), Response{
+			Success: false,
+			Message: fmt.Sprintf("Failed to reboot VM: %v", err),
+		})
+		return
+	}
+
+	c.JSON(http.StatusOK, Response{
+		Success: true,
+		Message: "VM rebooted successfully",
+	})
+}
+
 func (vmm *VMManager) deleteVMHandler(c *gin.Context) {
 	vmID := c.Param("id")
 	if err := vmm.DeleteVM(vmID); err != nil {
-		c.JSON(http.StatusInternalServerError, Response{
+		c.JSON(errorStatus(err), Response{
 			Success: false,
 			Message: fmt.Sprintf("Failed to delete VM: %v", err),
 		})
@@ -732,6 +1582,131 @@ func (vmm *VMManager) deleteVMHandler(c *gin.Context) {
 	})
 }
 
+func (vmm *VMManager) updateVMHandler(c *gin.Context) {
+	var req UpdateRequest
+	if err := c.ShouldBindJSON(&req); err != nil {
+		c.JSON(http.StatusBadRequest, Response{
+			Success: false,
+			Message: fmt.Sprintf("Invalid request: %v", err),
+		})
+		return
+	}
+
+	vm, err := vmm.UpdateVM(c.Param("id"), req)
+	if err != nil {
+		c.JSON(errorStatus(err), Response{
+			Success: false,
+			Message: fmt.Sprintf("Failed to update VM: %v", err),
+		})
+		return
+	}
+
+	c.JSON(http.StatusOK, Response{
+		Success: true,
+		Message: "VM updated successfully",
+		Data:    vm,
+	})
+}
+
+func (vmm *VMManager) resizeVMHandler(c *gin.Context) {
+	var req ResizeRequest
+	if err := c.ShouldBindJSON(&req); err != nil {
+		c.JSON(http.StatusBadRequest, Response{
+			Success: false,
+			Message: fmt.Sprintf("Invalid request: %v", err),
+		})
+		return
+	}
+
+	vm, err := vmm.ResizeVM(c.Param("id"), req)
+	if err != nil {
+		c.JSON(errorStatus(err), Response{
+			Success: false,
+			Message: fmt.Sprintf("Failed to resize VM: %v", err),
+		})
+		return
+	}
+
+	c.JSON(http.StatusOK, Response{
+		Success: true,
+		Message: "VM resized successfully",
+		Data:    vm,
+	})
+}
+
+func (vmm *VMManager) createSnapshotHandler(c *gin.Context) {
+	var req SnapshotRequest
+	if err := c.ShouldBindJSON(&req); err != nil {
+		c.JSON(http.StatusBadRequest, Response{
+			Success: false,
+			Message: fmt.Sprintf("Invalid request: %v", err),
+		})
+		return
+	}
+
+	snapshot, err := vmm.CreateSnapshot(c.Param("id"), req.Type)
+	if err != nil {
+		c.JSON(errorStatus(err), Response{
+			Success: false,
+			Message: fmt.Sprintf("Failed to create snapshot: %v", err),
+		})
+		return
+	}
+
+	c.JSON(http.StatusCreated, Response{
+		Success: true,
+		Message: "Snapshot created successfully",
+		Data:    snapshot,
+	})
+}
+
+func (vmm *VMManager) listSnapshotsHandler(c *gin.Context) {
+	snapshots, err := vmm.ListSnapshots(c.Param("id"))
+	if err != nil {
+		c.JSON(http.StatusNotFound, Response{
+			Success: false,
+			Message: err.Error(),
+		})
+		return
+	}
+
+	c.JSON(http.StatusOK, Response{
+		Success: true,
+		Message: "Snapshots retrieved successfully",
+		Data:    snapshots,
+	})
+}
+
+func (vmm *VMManager) restoreSnapshotHandler(c *gin.Context) {
+	if err := vmm.RestoreSnapshot(c.Param("id"), c.Param("snapshot_id")); err != nil {
+		c.JSON(errorStatus(err), Response{
+			Success: false,
+			Message: fmt.Sprintf("Failed to restore snapshot: %v", err),
+		})
+		return
+	}
+
+	c.JSON(http.StatusOK, Response{
+		Success: true,
+		Message: "Snapshot restored successfully",
+	})
+}
+
+func (vmm *VMManager) deleteSnapshotHandler(c *gin.Context) {
+	if err := vmm.DeleteSnapshot(c.Param("id"), c.Param("snapshot_id")); err != nil {
+		c.JSON(errorStatus(err), Response{
+			Success: false,
+			Message: fmt.Sprintf("Failed to delete snapshot: %v", err),
+		})
+		return
+	}
+
+	c.JSON(http.StatusOK, Response{
+		Success: true,
+		Message: "Snapshot deleted successfully",
+	})
+}
+
 func setupRouter(vmManager *VMManager) *gin.Engine {
 	r := gin.Default()
 
@@ -754,9 +1729,19 @@ func setupRouter(vmManager *VMManager) *gin.Engine {
 		api.POST("/vms", vmManager.createVMHandler)
 		api.GET("/vms", vmManager.listVMsHandler)
 		api.GET("/vms/:id", vmManager.getVMHandler)
+		api.PATCH("/vms/:id", vmManager.updateVMHandler)
 		api.POST("/vms/:id/start", vmManager.startVMHandler)
 		api.POST("/vms/:id/stop", vmManager.stopVMHandler)
+		api.POST("/vms/:id/reboot", vmManager.rebootVMHandler)
+		api.POST("/vms/:id/clone", vmManager.cloneVMHandler)
+		api.POST("/vms/:id/resize", vmManager.resizeVMHandler)
+		api.POST("/vms/:id/pause", vmManager.pauseVMHandler)
+		api.POST("/vms/:id/resume", vmManager.resumeVMHandler)
 		api.DELETE("/vms/:id", vmManager.deleteVMHandler)
+		api.POST("/vms/:id/snapshots", vmManager.createSnapshotHandler)
+		api.GET("/vms/:id/snapshots", vmManager.listSnapshotsHandler)
+		api.POST("/vms/:id/snapshots/:snapshot_id/restore", vmManager.restoreSnapshotHandler)
+		api.DELETE("/vms/:id/snapshots/:snapshot_id", vmManager.deleteSnapshotHandler)
 	}
 
 	return r
@@ -779,6 +1764,20 @@ func main() {
 		FullTimestamp:   true,
 	})
 
+	if config.APISocket != "" {
+		// Remove a stale socket left by a previous run
+		os.Remove(config.APISocket)
+		listener, err := net.Listen("unix", config.APISocket)
+		if err != nil {
+			logger.Fatalf("Failed to listen on %s: %v", config.APISocket, err)
+		}
+		logger.Infof("Starting Firecracker VPS API server on unix socket %s", config.APISocket)
+		if err := http.Serve(listener, router); err != nil {
+			logger.Fatalf("Failed to start server: %v", err)
+		}
+		return
+	}
+
 	logger.Infof("Starting Firecracker VPS API server on port %s", config.APIPort)
 	if err := http.ListenAndServe(":"+config.APIPort, router); err != nil {
 		logger.Fatalf("Failed to start server: %v", err)